@@ -0,0 +1,424 @@
+/*!
+Scores of predicted protein complexes against reference complexes.
+
+The reference complexes are read with [`read_complexes`] from a file with one complex per line and tab-separated protein labels,
+the format written by [`LabeledGraph::write_clusters`](crate::labeled::LabeledGraph::write_clusters), with the labels of the clustered graph.
+Predicted complexes are the clusters of any algorithm, as node indices.
+
+- [`maximum_matching_ratio`]: mean neighborhood affinity of a maximum weight one-to-one matching of the reference complexes to the predicted
+  ones (Nepusz et al. 2012)
+- [`affinity_scores`]: precision, recall and F1 of the complexes that match a complex of the other side with a neighborhood affinity
+  of at least a threshold (Bader and Hogue 2003)
+- [`sensitivity`], [`positive_predictive_value`], [`accuracy`] and [`separation`]: the clustering-wise scores of Brohée and van Helden (2006)
+  on the number of proteins shared by every reference and predicted complex
+
+The neighborhood affinity of two complexes `A` and `B` is `|A ∩ B|^2 / (|A| |B|)`.
+
+```rust
+use graph_clustering_rs::benchmark::*;
+use graph_clustering_rs::labeled::LabeledGraph;
+
+let labeled = LabeledGraph::from_abc("a b\nb c\nc a\nc d\nd e\ne f\nf d\n".as_bytes()).unwrap();
+// g is not in the graph, but it still belongs to the second complex
+let reference = read_complexes("a\tb\tc\nd\te\tf\tg\n".as_bytes(), &labeled.labels).unwrap();
+assert_eq!(reference, vec![vec![0, 1, 2], vec![3, 4, 5, 6]]);
+
+let predicted = vec![vec![0, 1, 2], vec![3, 4, 5]];
+assert_eq!(affinity_scores(&predicted, &reference, 0.2).f1, 1.);
+assert!((maximum_matching_ratio(&predicted, &reference) - (1. + 0.75) / 2.).abs() < 1e-12);
+assert_eq!(sensitivity(&predicted, &reference), 6. / 7.);
+assert_eq!(positive_predictive_value(&predicted, &reference), 1.);
+```
+*/
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::labeled::Labels;
+use crate::union_find::UnionFind;
+
+/// Read complexes with one complex per line and tab-separated labels, or whitespace-separated labels on lines without a tab
+///
+/// Labels are numbered by `labels`, and labels that are not among them are numbered after them in order of first appearance,
+/// so that they count in the sizes of the complexes but are never predicted. Empty lines and lines starting with `#` are skipped.
+pub fn read_complexes<R: BufRead>(reader: R, labels: &Labels) -> Result<Vec<Vec<usize>>> {
+    let mut unknown: HashMap<String, usize> = HashMap::new();
+    let mut complexes = Vec::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // labels may contain spaces if they are separated by tabs
+        let fields: Vec<&str> = if line.contains('\t') {
+            line.split('\t').map(str::trim).filter(|label| !label.is_empty()).collect()
+        } else {
+            line.split_whitespace().collect()
+        };
+        let mut complex: Vec<usize> = fields.into_iter()
+            .map(|label| match labels.index(label) {
+                Some(i) => i,
+                None => {
+                    let next = labels.len() + unknown.len();
+                    *unknown.entry(label.to_string()).or_insert(next)
+                }
+            })
+            .collect();
+        complex.sort_unstable();
+        complex.dedup();
+        complexes.push(complex);
+    }
+
+    Ok(complexes)
+}
+
+/// Read a file of complexes, see [`read_complexes`]
+pub fn read_complexes_file<P: AsRef<Path>>(path: P, labels: &Labels) -> Result<Vec<Vec<usize>>> {
+    let path = path.as_ref();
+    let file = File::open(path).with_context(|| format!("cannot open {}", path.display()))?;
+    read_complexes(BufReader::new(file), labels)
+}
+
+/// Neighborhood affinity of two complexes, 0 if either is empty
+pub fn neighborhood_affinity(a: &[usize], b: &[usize]) -> f64 {
+    let (a, b) = (_set(a), _set(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.;
+    }
+    let shared = a.iter().filter(|i| b.binary_search(i).is_ok()).count() as f64;
+    shared * shared / (a.len() * b.len()) as f64
+}
+
+/// Precision, recall and F1 of the complexes matched by neighborhood affinity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AffinityScores {
+    /// Fraction of the predicted complexes that match a reference complex
+    pub precision: f64,
+    /// Fraction of the reference complexes that match a predicted complex
+    pub recall: f64,
+    /// Harmonic mean of the precision and the recall
+    pub f1: f64,
+}
+
+/// Complexes that match with a neighborhood affinity of at least `threshold`, 0.2 in MCODE and 0.25 in ClusterONE
+pub fn affinity_scores(predicted: &[Vec<usize>], reference: &[Vec<usize>], threshold: f64) -> AffinityScores {
+    let affinities = _affinities(predicted, reference);
+    let mut matched_predicted = vec![false; predicted.len()];
+    let mut matched_reference = vec![false; reference.len()];
+    for (&(r, p), &na) in affinities.iter() {
+        if na >= threshold {
+            matched_reference[r] = true;
+            matched_predicted[p] = true;
+        }
+    }
+
+    let fraction = |matched: &[bool]| {
+        if matched.is_empty() { 0. } else { matched.iter().filter(|&&m| m).count() as f64 / matched.len() as f64 }
+    };
+    let precision = fraction(&matched_predicted);
+    let recall = fraction(&matched_reference);
+    let f1 = if precision + recall > 0. { 2. * precision * recall / (precision + recall) } else { 0. };
+
+    AffinityScores { precision, recall, f1 }
+}
+
+/// Total neighborhood affinity of a maximum weight matching over the number of reference complexes, 0 without them
+pub fn maximum_matching_ratio(predicted: &[Vec<usize>], reference: &[Vec<usize>]) -> f64 {
+    if reference.is_empty() {
+        return 0.;
+    }
+    let affinities = _affinities(predicted, reference);
+
+    // the matching is solved separately on every connected component of the complexes with a positive affinity
+    let mut components = UnionFind::new(reference.len() + predicted.len());
+    for &(r, p) in affinities.keys() {
+        components.union(r, reference.len() + p);
+    }
+    let mut groups: HashMap<usize, (Vec<usize>, Vec<usize>)> = HashMap::new();
+    for r in 0..reference.len() {
+        groups.entry(components.find(r)).or_default().0.push(r);
+    }
+    for p in 0..predicted.len() {
+        groups.entry(components.find(reference.len() + p)).or_default().1.push(p);
+    }
+
+    let mut total = 0.;
+    for (refs, preds) in groups.values() {
+        if refs.is_empty() || preds.is_empty() {
+            continue;
+        }
+        // rows must not outnumber columns
+        let transposed = refs.len() > preds.len();
+        let (rows, cols) = if transposed { (preds, refs) } else { (refs, preds) };
+        let weight = |i: usize, j: usize| {
+            let key = if transposed { (cols[j], rows[i]) } else { (rows[i], cols[j]) };
+            affinities.get(&key).copied().unwrap_or(0.)
+        };
+
+        let cost: Vec<Vec<f64>> = (0..rows.len()).map(|i| (0..cols.len()).map(|j| -weight(i, j)).collect()).collect();
+        total += _assignment(&cost).into_iter().enumerate().map(|(i, j)| weight(i, j)).sum::<f64>();
+    }
+
+    total / reference.len() as f64
+}
+
+/// Clustering-wise sensitivity, the fraction of the proteins of every reference complex in its best predicted complex,
+/// weighted by the complex sizes
+pub fn sensitivity(predicted: &[Vec<usize>], reference: &[Vec<usize>]) -> f64 {
+    let table = _Contingency::new(predicted, reference);
+    let mut best = vec![0; reference.len()];
+    for (&(r, _), &t) in table.cells.iter() {
+        best[r] = best[r].max(t);
+    }
+
+    let total: usize = table.reference_sizes.iter().sum();
+    if total == 0 { 0. } else { best.iter().sum::<usize>() as f64 / total as f64 }
+}
+
+/// Positive predictive value, the fraction of the shared proteins of every predicted complex in its best reference complex,
+/// weighted by the number of shared proteins
+pub fn positive_predictive_value(predicted: &[Vec<usize>], reference: &[Vec<usize>]) -> f64 {
+    let table = _Contingency::new(predicted, reference);
+    let mut best = vec![0; predicted.len()];
+    for (&(_, p), &t) in table.cells.iter() {
+        best[p] = best[p].max(t);
+    }
+
+    let total: usize = table.predicted_shared.iter().sum();
+    if total == 0 { 0. } else { best.iter().sum::<usize>() as f64 / total as f64 }
+}
+
+/// Geometric accuracy, the geometric mean of the sensitivity and the positive predictive value
+pub fn accuracy(predicted: &[Vec<usize>], reference: &[Vec<usize>]) -> f64 {
+    (sensitivity(predicted, reference) * positive_predictive_value(predicted, reference)).sqrt()
+}
+
+/// Separation, the geometric mean of the mean separations of the reference and of the predicted complexes
+///
+/// The separation of a pair of complexes is the product of the fractions of their shared proteins among the proteins that each
+/// shares with any complex of the other side. It is 1 for a one-to-one correspondence.
+pub fn separation(predicted: &[Vec<usize>], reference: &[Vec<usize>]) -> f64 {
+    if predicted.is_empty() || reference.is_empty() {
+        return 0.;
+    }
+    let table = _Contingency::new(predicted, reference);
+
+    let mut reference_separation = vec![0.; reference.len()];
+    let mut predicted_separation = vec![0.; predicted.len()];
+    for (&(r, p), &t) in table.cells.iter() {
+        let t = t as f64;
+        let sep = t * t / (table.reference_shared[r] as f64 * table.predicted_shared[p] as f64);
+        reference_separation[r] += sep;
+        predicted_separation[p] += sep;
+    }
+
+    let mean = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+    (mean(&reference_separation) * mean(&predicted_separation)).sqrt()
+}
+
+/// Sorted and deduplicated complex
+fn _set(complex: &[usize]) -> Vec<usize> {
+    let mut set = complex.to_vec();
+    set.sort_unstable();
+    set.dedup();
+    set
+}
+
+/// Number of proteins shared by every pair of a reference and a predicted complex that intersect
+struct _Contingency {
+    cells: HashMap<(usize, usize), usize>,
+    reference_sizes: Vec<usize>,
+    /// Number of proteins every complex shares with all the complexes of the other side
+    reference_shared: Vec<usize>,
+    predicted_shared: Vec<usize>,
+}
+
+impl _Contingency {
+    fn new(predicted: &[Vec<usize>], reference: &[Vec<usize>]) -> Self {
+        let reference: Vec<Vec<usize>> = reference.iter().map(|c| _set(c)).collect();
+        let mut complexes_of: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (r, complex) in reference.iter().enumerate() {
+            for &protein in complex.iter() {
+                complexes_of.entry(protein).or_default().push(r);
+            }
+        }
+
+        let mut cells = HashMap::new();
+        let mut reference_shared = vec![0; reference.len()];
+        let mut predicted_shared = vec![0; predicted.len()];
+        for (p, complex) in predicted.iter().enumerate() {
+            for protein in _set(complex) {
+                for &r in complexes_of.get(&protein).into_iter().flatten() {
+                    *cells.entry((r, p)).or_insert(0) += 1;
+                    reference_shared[r] += 1;
+                    predicted_shared[p] += 1;
+                }
+            }
+        }
+
+        Self { cells, reference_sizes: reference.iter().map(|c| c.len()).collect(), reference_shared, predicted_shared }
+    }
+}
+
+/// Neighborhood affinity of every pair of a reference and a predicted complex that intersect
+fn _affinities(predicted: &[Vec<usize>], reference: &[Vec<usize>]) -> HashMap<(usize, usize), f64> {
+    let table = _Contingency::new(predicted, reference);
+    table.cells.iter()
+        .map(|(&(r, p), &t)| {
+            let t = t as f64;
+            ((r, p), t * t / (table.reference_sizes[r] * _set(&predicted[p]).len()) as f64)
+        })
+        .collect()
+}
+
+/// Column assigned to every row by the Hungarian algorithm, minimizing the total cost, with no more rows than columns
+fn _assignment(cost: &[Vec<f64>]) -> Vec<usize> {
+    let n = cost.len();
+    let m = cost.first().map_or(0, |row| row.len());
+
+    // potentials and matching of the columns, 1-indexed with the column 0 as a sentinel
+    let mut u = vec![0.; n + 1];
+    let mut v = vec![0.; m + 1];
+    let mut row_of = vec![0; m + 1];
+    let mut way = vec![0; m + 1];
+
+    for i in 1..=n {
+        row_of[0] = i;
+        let mut j0 = 0;
+        let mut min = vec![f64::INFINITY; m + 1];
+        let mut used = vec![false; m + 1];
+
+        loop {
+            used[j0] = true;
+            let i0 = row_of[j0];
+            let mut delta = f64::INFINITY;
+            let mut j1 = 0;
+            for j in 1..=m {
+                if used[j] {
+                    continue;
+                }
+                let reduced = cost[i0 - 1][j - 1] - u[i0] - v[j];
+                if reduced < min[j] {
+                    min[j] = reduced;
+                    way[j] = j0;
+                }
+                if min[j] < delta {
+                    delta = min[j];
+                    j1 = j;
+                }
+            }
+            for j in 0..=m {
+                if used[j] {
+                    u[row_of[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if row_of[j0] == 0 {
+                break;
+            }
+        }
+
+        while j0 != 0 {
+            let j1 = way[j0];
+            row_of[j0] = row_of[j1];
+            j0 = j1;
+        }
+    }
+
+    let mut assignment = vec![0; n];
+    for j in 1..=m {
+        if row_of[j] != 0 {
+            assignment[row_of[j] - 1] = j - 1;
+        }
+    }
+    assignment
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use crate::clustering::Clustering;
+    use crate::labeled::LabeledGraph;
+
+    fn complexes() -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+        // the first predicted complex takes one protein of the second reference complex
+        let predicted = vec![vec![0, 1, 2, 3], vec![4, 5]];
+        let reference = vec![vec![0, 1, 2], vec![3, 4, 5]];
+        (predicted, reference)
+    }
+
+    #[test]
+    fn test_brohee() {
+        let (predicted, reference) = complexes();
+        assert_abs_diff_eq!(sensitivity(&predicted, &reference), 5. / 6., epsilon = 1e-12);
+        assert_abs_diff_eq!(positive_predictive_value(&predicted, &reference), 5. / 6., epsilon = 1e-12);
+        assert_abs_diff_eq!(accuracy(&predicted, &reference), 5. / 6., epsilon = 1e-12);
+        assert_abs_diff_eq!(separation(&predicted, &reference), 0.75, epsilon = 1e-12);
+
+        assert_abs_diff_eq!(separation(&reference, &reference), 1., epsilon = 1e-12);
+        assert_eq!(accuracy(&[], &reference), 0.);
+    }
+
+    #[test]
+    fn test_affinity() {
+        let (mut predicted, reference) = complexes();
+        assert_eq!(neighborhood_affinity(&predicted[0], &reference[0]), 0.75);
+        assert_abs_diff_eq!(maximum_matching_ratio(&predicted, &reference), (0.75 + 4. / 6.) / 2., epsilon = 1e-12);
+
+        // a predicted complex of unknown proteins matches nothing
+        predicted.push(vec![6, 7]);
+        let scores = affinity_scores(&predicted, &reference, 0.25);
+        assert_abs_diff_eq!(scores.precision, 2. / 3., epsilon = 1e-12);
+        assert_eq!(scores.recall, 1.);
+        assert_abs_diff_eq!(scores.f1, 0.8, epsilon = 1e-12);
+
+        let strict = affinity_scores(&predicted, &reference, 0.7);
+        assert_eq!((strict.precision, strict.recall), (1. / 3., 0.5));
+    }
+
+    #[test]
+    fn test_matching() {
+        // matching the heaviest pair first gives 0.5, the best matching 0.85
+        let cost = vec![vec![-0.5, -0.4], vec![-0.45, 0.]];
+        assert_eq!(_assignment(&cost), vec![1, 0]);
+        assert_eq!(_assignment(&[vec![3., 1., 2.]]), vec![1]);
+
+        // one reference complex can only be matched once
+        let predicted = vec![vec![0, 1], vec![0, 1, 2]];
+        let reference = vec![vec![0, 1, 2]];
+        assert_eq!(maximum_matching_ratio(&predicted, &reference), 1.);
+        assert_eq!(maximum_matching_ratio(&predicted, &[]), 0.);
+    }
+
+    #[test]
+    fn test_read_complexes() {
+        let mut labels = Labels::new();
+        labels.intern("a");
+        labels.intern("b");
+
+        let complexes = read_complexes("b a a\n\n# comment\nx b y\ny\n".as_bytes(), &labels).unwrap();
+        assert_eq!(complexes, vec![vec![0, 1], vec![1, 2, 3], vec![3]]);
+    }
+
+    #[test]
+    fn test_write_and_read_complexes() {
+        let labeled = LabeledGraph::from_abc("protein A\tprotein B\nprotein B\tprotein C\nprotein C\tprotein D\n".as_bytes()).unwrap();
+        let clustering = Clustering::from_membership(&[0, 0, 1, 1]);
+        let mut written = Vec::new();
+        labeled.write_clusters(&clustering, &mut written).unwrap();
+
+        let complexes = read_complexes(written.as_slice(), &labeled.labels).unwrap();
+        assert_eq!(complexes, vec![vec![0, 1], vec![2, 3]]);
+    }
+}
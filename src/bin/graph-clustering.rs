@@ -0,0 +1,464 @@
+//! Command line interface of graph-clustering-rs.
+//!
+//! Every algorithm is a subcommand that reads a graph, clusters it and writes one cluster per line with tab-separated labels,
+//! as `mcl --abc` does. A summary of the run is printed to stderr.
+//!
+//! ```text
+//! graph-clustering mcl network.abc --inflation 1.4 -o clusters.txt
+//! graph-clustering louvain edges.txt --format edges --resolution 0.5
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use anyhow::{ensure, Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use graph_clustering_rs::clusterer::{Algorithm, GraphClusterer};
+use graph_clustering_rs::coach::CoachParams;
+use graph_clustering_rs::dpclus::DpclusParams;
+use graph_clustering_rs::graph_entropy::GraphEntropyParams;
+use graph_clustering_rs::hcca::HccaParams;
+use graph_clustering_rs::ipca::IpcaParams;
+use graph_clustering_rs::labeled::LabeledGraph;
+use graph_clustering_rs::leiden::{LeidenParams, Quality};
+use graph_clustering_rs::louvain::LouvainParams;
+use graph_clustering_rs::mcl::MclParams;
+use graph_clustering_rs::mcode::McodeParams;
+
+#[derive(Debug, Parser)]
+#[command(name = "graph-clustering", version, about = "Cluster graphs read from ABC, edge list or matrix files")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Markov clustering
+    Mcl(MclArgs),
+    /// Molecular complex detection
+    Mcode(McodeArgs),
+    /// Louvain modularity optimization
+    Louvain(LouvainArgs),
+    /// Leiden algorithm
+    Leiden(LeidenArgs),
+    /// Hierarchical clustering with connectivity
+    Hcca(HccaArgs),
+    /// Density-periphery based clustering
+    Dpclus(DpclusArgs),
+    /// Interaction probability based clustering
+    Ipca(IpcaArgs),
+    /// Core-attachment clustering
+    Coach(CoachArgs),
+    /// Graph entropy clustering
+    GraphEntropy(GraphEntropyArgs),
+}
+
+/// Format of the input graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Two labels and an optional weight per line
+    Abc,
+    /// Two integer node IDs and an optional weight per line
+    Edges,
+    /// Symmetric adjacency matrix, one row per line
+    Matrix,
+}
+
+#[derive(Debug, Args)]
+struct Io {
+    /// Graph to cluster
+    input: PathBuf,
+    /// Format of the input
+    #[arg(short, long, value_enum, default_value_t = Format::Abc)]
+    format: Format,
+    /// Cluster file to write, stdout if not given
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct MclArgs {
+    #[command(flatten)]
+    io: Io,
+    /// Power of the expansion
+    #[arg(short, long)]
+    expansion: Option<f64>,
+    /// Power of the inflation, larger values give smaller clusters
+    #[arg(short = 'I', long)]
+    inflation: Option<f64>,
+    /// Weight of the self loops added to every node
+    #[arg(long)]
+    loop_value: Option<f64>,
+    /// Maximum number of iterations
+    #[arg(long)]
+    iterations: Option<usize>,
+    /// Entries below 1 / P are pruned, as `-P` of mcl
+    #[arg(short = 'P')]
+    prune: Option<f64>,
+    /// Entries below this value are pruned
+    #[arg(long, conflicts_with = "prune")]
+    prune_threshold: Option<f64>,
+    /// Keep at most this many entries per column
+    #[arg(short = 'S', long)]
+    select: Option<usize>,
+    /// Recover up to this many entries per column if too much mass was pruned
+    #[arg(short = 'R', long)]
+    recover: Option<usize>,
+    /// Fraction of the column mass that recovery aims for
+    #[arg(long)]
+    pct: Option<f64>,
+}
+
+#[derive(Debug, Args)]
+struct McodeArgs {
+    #[command(flatten)]
+    io: Io,
+    /// Nodes whose weight is at least (1 - cutoff) times the seed weight join its cluster
+    #[arg(long)]
+    node_score_cutoff: Option<f64>,
+    /// Keep the nodes singly connected to the cluster
+    #[arg(long)]
+    no_haircut: bool,
+    /// Add the neighbours of the cluster whose neighbourhood is dense enough
+    #[arg(long)]
+    fluff: bool,
+    /// Density a node needs to fluff the cluster
+    #[arg(long)]
+    fluff_node_density_cutoff: Option<f64>,
+    /// Drop the clusters without a k-core of this k
+    #[arg(long)]
+    k_core: Option<usize>,
+    /// Keep the clusters without a k-core
+    #[arg(long, conflicts_with = "k_core")]
+    no_k_core: bool,
+    /// Maximum distance from the seed
+    #[arg(long)]
+    max_depth: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+struct LouvainArgs {
+    #[command(flatten)]
+    io: Io,
+    /// Resolution of the modularity, larger values give smaller communities
+    #[arg(short, long)]
+    resolution: Option<f64>,
+    /// Seed of the node order, the node indices are used if not given
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Minimum gain of modularity for another pass
+    #[arg(long)]
+    tolerance: Option<f64>,
+}
+
+/// Quality function of the Leiden algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum QualityArg {
+    Modularity,
+    Cpm,
+}
+
+#[derive(Debug, Args)]
+struct LeidenArgs {
+    #[command(flatten)]
+    io: Io,
+    /// Quality function to optimize
+    #[arg(short, long, value_enum)]
+    quality: Option<QualityArg>,
+    /// Resolution of the quality function
+    #[arg(short, long)]
+    resolution: Option<f64>,
+    /// Randomness of the refinement
+    #[arg(long)]
+    randomness: Option<f64>,
+    /// Seed of the random node order and refinement, greedy in index order if not given
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Number of iterations
+    #[arg(long)]
+    iterations: Option<usize>,
+    /// Iterate until the partition does not change
+    #[arg(long, conflicts_with = "iterations")]
+    until_stable: bool,
+}
+
+#[derive(Debug, Args)]
+struct HccaArgs {
+    #[command(flatten)]
+    io: Io,
+    /// Depth of the node vicinity network around every seed
+    #[arg(long)]
+    step_size: Option<usize>,
+    /// Minimum number of nodes of a cluster
+    #[arg(long)]
+    min_cluster_size: Option<usize>,
+    /// Maximum number of nodes of a cluster
+    #[arg(long)]
+    max_cluster_size: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+struct DpclusArgs {
+    #[command(flatten)]
+    io: Io,
+    /// Minimum density of a cluster
+    #[arg(long)]
+    d_in: Option<f64>,
+    /// Minimum cluster property of a joining node
+    #[arg(long)]
+    cp_in: Option<f64>,
+    /// Let the nodes of a found cluster join later clusters
+    #[arg(long)]
+    overlapping: bool,
+    /// Use the edge weights as priorities instead of the number of common neighbours
+    #[arg(long)]
+    weighted: bool,
+}
+
+#[derive(Debug, Args)]
+struct IpcaArgs {
+    #[command(flatten)]
+    io: Io,
+    /// Minimum interaction probability of a joining node
+    #[arg(long)]
+    t_in: Option<f64>,
+    /// Maximum shortest path length within a cluster
+    #[arg(long)]
+    diameter: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+struct CoachArgs {
+    #[command(flatten)]
+    io: Io,
+    /// Minimum density of a core
+    #[arg(long)]
+    density_threshold: Option<f64>,
+    /// Cores whose overlap score is at least this value are merged
+    #[arg(long)]
+    overlap_threshold: Option<f64>,
+    /// Minimum number of nodes of a core
+    #[arg(long)]
+    min_core_size: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+struct GraphEntropyArgs {
+    #[command(flatten)]
+    io: Io,
+    /// Seed of the random seed nodes, the highest degree node is used if not given
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Minimum number of nodes of a cluster
+    #[arg(long)]
+    min_cluster_size: Option<usize>,
+}
+
+impl Command {
+    /// Input options and the algorithm with its validated parameters
+    fn algorithm(&self) -> Result<(&Io, Algorithm)> {
+        let res = match self {
+            Command::Mcl(args) => {
+                let mut params = MclParams::<f64>::default();
+                _set(&mut params.expansion, args.expansion);
+                _set(&mut params.inflation, args.inflation);
+                _set(&mut params.loop_value, args.loop_value);
+                _set(&mut params.iterations, args.iterations);
+                if let Some(prune) = args.prune {
+                    ensure!(prune > 0., "-P must be positive, got {}", prune);
+                    params.pruning.threshold = 1. / prune;
+                }
+                _set(&mut params.pruning.threshold, args.prune_threshold);
+                params.pruning.select = args.select.or(params.pruning.select);
+                params.pruning.recover = args.recover.or(params.pruning.recover);
+                params.pruning.pct = args.pct.or(params.pruning.pct);
+                params.validate()?;
+                (&args.io, Algorithm::Mcl(params))
+            }
+            Command::Mcode(args) => {
+                let mut params = McodeParams::<f64>::default();
+                _set(&mut params.node_score_cutoff, args.node_score_cutoff);
+                params.haircut &= !args.no_haircut;
+                params.fluff |= args.fluff;
+                _set(&mut params.fluff_node_density_cutoff, args.fluff_node_density_cutoff);
+                params.k_core = if args.no_k_core { None } else { args.k_core.or(params.k_core) };
+                _set(&mut params.max_depth, args.max_depth);
+                params.validate()?;
+                (&args.io, Algorithm::Mcode(params))
+            }
+            Command::Louvain(args) => {
+                let mut params = LouvainParams::default();
+                _set(&mut params.resolution, args.resolution);
+                params.seed = args.seed.or(params.seed);
+                _set(&mut params.tolerance, args.tolerance);
+                params.validate()?;
+                (&args.io, Algorithm::Louvain(params))
+            }
+            Command::Leiden(args) => {
+                let mut params = LeidenParams::default();
+                if let Some(quality) = args.quality {
+                    params.quality = match quality {
+                        QualityArg::Modularity => Quality::Modularity,
+                        QualityArg::Cpm => Quality::Cpm,
+                    };
+                }
+                _set(&mut params.resolution, args.resolution);
+                _set(&mut params.randomness, args.randomness);
+                params.seed = args.seed.or(params.seed);
+                params.iterations = if args.until_stable { None } else { args.iterations.or(params.iterations) };
+                params.validate()?;
+                (&args.io, Algorithm::Leiden(params))
+            }
+            Command::Hcca(args) => {
+                let mut params = HccaParams::default();
+                _set(&mut params.step_size, args.step_size);
+                _set(&mut params.min_cluster_size, args.min_cluster_size);
+                _set(&mut params.max_cluster_size, args.max_cluster_size);
+                params.validate()?;
+                (&args.io, Algorithm::Hcca(params))
+            }
+            Command::Dpclus(args) => {
+                let mut params = DpclusParams::<f64>::default();
+                _set(&mut params.d_in, args.d_in);
+                _set(&mut params.cp_in, args.cp_in);
+                params.overlapping |= args.overlapping;
+                params.weighted |= args.weighted;
+                params.validate()?;
+                (&args.io, Algorithm::Dpclus(params))
+            }
+            Command::Ipca(args) => {
+                let mut params = IpcaParams::<f64>::default();
+                _set(&mut params.t_in, args.t_in);
+                _set(&mut params.diameter, args.diameter);
+                params.validate()?;
+                (&args.io, Algorithm::Ipca(params))
+            }
+            Command::Coach(args) => {
+                let mut params = CoachParams::<f64>::default();
+                _set(&mut params.density_threshold, args.density_threshold);
+                _set(&mut params.overlap_threshold, args.overlap_threshold);
+                _set(&mut params.min_core_size, args.min_core_size);
+                params.validate()?;
+                (&args.io, Algorithm::Coach(params))
+            }
+            Command::GraphEntropy(args) => {
+                let mut params = GraphEntropyParams::default();
+                params.seed = args.seed.or(params.seed);
+                _set(&mut params.min_cluster_size, args.min_cluster_size);
+                params.validate()?;
+                (&args.io, Algorithm::GraphEntropy(params))
+            }
+        };
+        Ok(res)
+    }
+}
+
+/// Overwrite a default parameter with the value given on the command line
+fn _set<T>(param: &mut T, value: Option<T>) {
+    if let Some(value) = value {
+        *param = value;
+    }
+}
+
+fn _read(io: &Io) -> Result<LabeledGraph> {
+    match io.format {
+        Format::Abc => LabeledGraph::read_abc(&io.input),
+        Format::Edges => LabeledGraph::read_edge_list(&io.input),
+        Format::Matrix => LabeledGraph::read_matrix(&io.input),
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let (io, algorithm) = cli.command.algorithm()?;
+    let labeled = _read(io)?;
+
+    let start = Instant::now();
+    let clustering = algorithm.cluster(&labeled.graph)?;
+    let elapsed = start.elapsed();
+
+    match &io.output {
+        Some(path) => {
+            let file = File::create(path).with_context(|| format!("cannot create {}", path.display()))?;
+            let mut writer = BufWriter::new(file);
+            labeled.write_clusters(&clustering, &mut writer)?;
+            writer.flush()?;
+        }
+        None => {
+            let stdout = io::stdout();
+            let mut writer = BufWriter::new(stdout.lock());
+            labeled.write_clusters(&clustering, &mut writer)?;
+            writer.flush()?;
+        }
+    }
+
+    let unassigned = clustering.unassigned().len();
+    eprintln!("algorithm:  {}", algorithm.name());
+    eprintln!("nodes:      {}", labeled.graph.node_count());
+    eprintln!("edges:      {}", labeled.graph.edge_count());
+    eprintln!("clusters:   {}", clustering.len());
+    eprintln!("largest:    {}", clustering.sizes().into_iter().max().unwrap_or(0));
+    eprintln!("clustered:  {}", clustering.node_count() - unassigned);
+    eprintln!("unassigned: {}", unassigned);
+    eprintln!("overlap:    {}", if clustering.is_overlapping() { "yes" } else { "no" });
+    eprintln!("time:       {:.3}s", elapsed.as_secs_f64());
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("error: {:#}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_run() {
+        let dir = std::env::temp_dir().join(format!("graph-clustering-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("triangles.abc");
+        let output = dir.join("clusters.txt");
+
+        // two triangles joined by the edge c d
+        fs::write(&input, "a\tb\na\tc\nb\tc\nc\td\t0.1\nd\te\nd\tf\ne\tf\n").unwrap();
+
+        let cli = Cli::parse_from(["graph-clustering", "louvain", input.to_str().unwrap(), "-o", output.to_str().unwrap()]);
+        run(cli).unwrap();
+        let clusters = fs::read_to_string(&output).unwrap();
+        assert_eq!(clusters.lines().collect::<Vec<_>>(), vec!["a\tb\tc", "d\te\tf"]);
+
+        let cli = Cli::parse_from(["graph-clustering", "mcl", dir.join("missing.abc").to_str().unwrap()]);
+        assert!(run(cli).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn _mcl_params(args: &[&str]) -> Result<MclParams<f64>> {
+        let cli = Cli::parse_from(["graph-clustering", "mcl", "network.abc"].iter().chain(args));
+        match cli.command.algorithm()? {
+            (_, Algorithm::Mcl(params)) => Ok(params),
+            (_, algorithm) => panic!("expected mcl, got {}", algorithm.name()),
+        }
+    }
+
+    #[test]
+    fn test_mcl_prune() {
+        let params = _mcl_params(&["-P", "4000"]).unwrap();
+        assert_eq!(params.pruning.threshold, 1. / 4000.);
+
+        let params = _mcl_params(&["--prune-threshold", "0.001"]).unwrap();
+        assert_eq!(params.pruning.threshold, 0.001);
+
+        assert!(_mcl_params(&["-P", "0"]).is_err());
+        assert!(Cli::try_parse_from(["graph-clustering", "mcl", "network.abc", "-P", "10", "--prune-threshold", "0.1"]).is_err());
+    }
+}
@@ -0,0 +1,426 @@
+/*!
+Common interface of the clustering algorithms.
+
+Every algorithm implements [`GraphClusterer`] on a wrapper of its parameters, so that pipelines can run any of them on a petgraph
+graph with numeric edge weights and get the clusters back as a [`Clustering`]. [`Algorithm`] chooses the algorithm at runtime,
+for example from a configuration file with the `serde` feature.
+
+The graph is converted to the input each algorithm expects:
+
+- MCL runs on the adjacency matrix built by [`MclGraphExt`], with the edges of directed graphs made to go both ways by [`Symmetrize::Add`]
+- MCODE, DPClus, IPCA and CoAch use the edge weights as `f64`, and compute their own node weights
+- HCCA ignores the edge weights, and the nodes it leaves unassigned are not reported
+
+# Example Usage
+
+```rust
+use graph_clustering_rs::clusterer::*;
+use graph_clustering_rs::louvain::LouvainParams;
+use graph_clustering_rs::mcode::McodeParams;
+use petgraph::graph::UnGraph;
+
+// a 4-clique with the leaf 4 attached to 3
+let graph = UnGraph::<(), f64>::from_edges(&[(0, 1, 1.), (0, 2, 1.), (0, 3, 1.), (1, 2, 1.), (1, 3, 1.), (2, 3, 1.), (3, 4, 1.)]);
+
+// code generic over the algorithm
+fn clustered<C: GraphClusterer>(clusterer: &C, graph: &UnGraph<(), f64>) -> usize {
+    let clustering = clusterer.cluster(graph).unwrap();
+    clustering.node_count() - clustering.unassigned().len()
+}
+
+// Louvain partitions every node, while MCODE leaves the leaf out of the complex
+let louvain = Louvain(LouvainParams::default());
+assert_eq!(louvain.params().resolution, 1.);
+assert_eq!(clustered(&louvain, &graph), 5);
+assert_eq!(clustered(&Mcode(McodeParams::default()), &graph), 4);
+
+// the algorithm is chosen at runtime
+let algorithm = Algorithm::Mcode(McodeParams::default());
+assert_eq!(algorithm.name(), "mcode");
+assert_eq!(algorithm.cluster(&graph).unwrap(), vec![vec![0, 1, 2, 3]]);
+
+// and read from a configuration with the serde feature
+#[cfg(feature = "serde")]
+{
+    let json = serde_json::to_string(&algorithm).unwrap();
+    assert!(json.starts_with(r#"{"algorithm":"mcode""#));
+    assert_eq!(serde_json::from_str::<Algorithm>(&json).unwrap(), algorithm);
+}
+```
+*/
+
+use anyhow::Result;
+use num_traits::Float;
+use petgraph::{EdgeType, graph::{Graph, IndexType}};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::clustering::{Clustering, ToClustering};
+use crate::coach::{CoachExt, CoachParams};
+use crate::dpclus::{DpclusExt, DpclusParams};
+use crate::graph_entropy::{GraphEntropyExt, GraphEntropyParams};
+use crate::hcca::{HccaExt, HccaParams};
+use crate::ipca::{IpcaExt, IpcaParams};
+use crate::leiden::{LeidenExt, LeidenParams};
+use crate::louvain::{LouvainExt, LouvainParams};
+use crate::mcl::{MclGraphExt, MclParams, Symmetrize};
+use crate::mcode::{McodeExt, McodeParams};
+
+/// Clustering algorithm run on petgraph graphs
+pub trait GraphClusterer {
+    /// Parameters of the algorithm
+    type Params;
+
+    /// Parameters used by [`cluster`](GraphClusterer::cluster)
+    fn params(&self) -> &Self::Params;
+
+    /// Clusters of the node indices of `graph`, in the order the algorithm reports them
+    ///
+    /// Clusters may overlap if the algorithm finds overlapping clusters.
+    fn cluster<N, E, Ty, Ix>(&self, graph: &Graph<N, E, Ty, Ix>) -> Result<Clustering>
+    where
+        E: Float,
+        Ty: EdgeType,
+        Ix: IndexType;
+}
+
+/// [MCL](crate::mcl)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mcl(pub MclParams<f64>);
+
+/// [MCODE](crate::mcode)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mcode(pub McodeParams<f64>);
+
+/// [Louvain](crate::louvain)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Louvain(pub LouvainParams);
+
+/// [Leiden](crate::leiden)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Leiden(pub LeidenParams);
+
+/// [HCCA](crate::hcca)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hcca(pub HccaParams);
+
+/// [DPClus](crate::dpclus)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dpclus(pub DpclusParams<f64>);
+
+/// [IPCA](crate::ipca)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ipca(pub IpcaParams<f64>);
+
+/// [CoAch](crate::coach)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coach(pub CoachParams<f64>);
+
+/// [Graph entropy clustering](crate::graph_entropy), with the edge weights
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphEntropy(pub GraphEntropyParams);
+
+impl GraphClusterer for Mcl {
+    type Params = MclParams<f64>;
+
+    fn params(&self) -> &Self::Params {
+        &self.0
+    }
+
+    fn cluster<N, E, Ty, Ix>(&self, graph: &Graph<N, E, Ty, Ix>) -> Result<Clustering>
+    where
+        E: Float,
+        Ty: EdgeType,
+        Ix: IndexType
+    {
+        graph.map(|_, _| (), |_, &w| w).mcl_symmetrized(&self.0, Symmetrize::Add)?.clusters.clustering(graph.node_count())
+    }
+}
+
+impl GraphClusterer for Mcode {
+    type Params = McodeParams<f64>;
+
+    fn params(&self) -> &Self::Params {
+        &self.0
+    }
+
+    fn cluster<N, E, Ty, Ix>(&self, graph: &Graph<N, E, Ty, Ix>) -> Result<Clustering>
+    where
+        E: Float,
+        Ty: EdgeType,
+        Ix: IndexType
+    {
+        _weighted(graph).mcode(&self.0)?.clustering(graph.node_count())
+    }
+}
+
+impl GraphClusterer for Louvain {
+    type Params = LouvainParams;
+
+    fn params(&self) -> &Self::Params {
+        &self.0
+    }
+
+    fn cluster<N, E, Ty, Ix>(&self, graph: &Graph<N, E, Ty, Ix>) -> Result<Clustering>
+    where
+        E: Float,
+        Ty: EdgeType,
+        Ix: IndexType
+    {
+        let res = graph.map(|_, _| (), |_, w| _to_f64(w)).louvain(&self.0)?;
+        Ok(res.clusters())
+    }
+}
+
+impl GraphClusterer for Leiden {
+    type Params = LeidenParams;
+
+    fn params(&self) -> &Self::Params {
+        &self.0
+    }
+
+    fn cluster<N, E, Ty, Ix>(&self, graph: &Graph<N, E, Ty, Ix>) -> Result<Clustering>
+    where
+        E: Float,
+        Ty: EdgeType,
+        Ix: IndexType
+    {
+        Ok(graph.leiden(&self.0)?.clusters())
+    }
+}
+
+impl GraphClusterer for Hcca {
+    type Params = HccaParams;
+
+    fn params(&self) -> &Self::Params {
+        &self.0
+    }
+
+    fn cluster<N, E, Ty, Ix>(&self, graph: &Graph<N, E, Ty, Ix>) -> Result<Clustering>
+    where
+        E: Float,
+        Ty: EdgeType,
+        Ix: IndexType
+    {
+        Ok(graph.hcca(&self.0)?.clustering())
+    }
+}
+
+impl GraphClusterer for Dpclus {
+    type Params = DpclusParams<f64>;
+
+    fn params(&self) -> &Self::Params {
+        &self.0
+    }
+
+    fn cluster<N, E, Ty, Ix>(&self, graph: &Graph<N, E, Ty, Ix>) -> Result<Clustering>
+    where
+        E: Float,
+        Ty: EdgeType,
+        Ix: IndexType
+    {
+        _weighted(graph).dpclus(&self.0)?.clustering(graph.node_count())
+    }
+}
+
+impl GraphClusterer for Ipca {
+    type Params = IpcaParams<f64>;
+
+    fn params(&self) -> &Self::Params {
+        &self.0
+    }
+
+    fn cluster<N, E, Ty, Ix>(&self, graph: &Graph<N, E, Ty, Ix>) -> Result<Clustering>
+    where
+        E: Float,
+        Ty: EdgeType,
+        Ix: IndexType
+    {
+        _weighted(graph).ipca(&self.0)?.clustering(graph.node_count())
+    }
+}
+
+impl GraphClusterer for Coach {
+    type Params = CoachParams<f64>;
+
+    fn params(&self) -> &Self::Params {
+        &self.0
+    }
+
+    fn cluster<N, E, Ty, Ix>(&self, graph: &Graph<N, E, Ty, Ix>) -> Result<Clustering>
+    where
+        E: Float,
+        Ty: EdgeType,
+        Ix: IndexType
+    {
+        _weighted(graph).coach(&self.0)?.clustering(graph.node_count())
+    }
+}
+
+impl GraphClusterer for GraphEntropy {
+    type Params = GraphEntropyParams;
+
+    fn params(&self) -> &Self::Params {
+        &self.0
+    }
+
+    fn cluster<N, E, Ty, Ix>(&self, graph: &Graph<N, E, Ty, Ix>) -> Result<Clustering>
+    where
+        E: Float,
+        Ty: EdgeType,
+        Ix: IndexType
+    {
+        graph.graph_entropy_weighted(&self.0, _to_f64)?.clustering(graph.node_count())
+    }
+}
+
+/// Algorithm chosen at runtime, with its parameters
+///
+/// With the `serde` feature the algorithm is read from the `algorithm` field in snake case, next to its parameters:
+/// `{"algorithm": "louvain", "resolution": 0.5}`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "algorithm", rename_all = "snake_case"))]
+pub enum Algorithm {
+    Mcl(MclParams<f64>),
+    Mcode(McodeParams<f64>),
+    Louvain(LouvainParams),
+    Leiden(LeidenParams),
+    Hcca(HccaParams),
+    Dpclus(DpclusParams<f64>),
+    Ipca(IpcaParams<f64>),
+    Coach(CoachParams<f64>),
+    GraphEntropy(GraphEntropyParams),
+}
+
+impl Algorithm {
+    /// Name of the algorithm, as in the `algorithm` field of the serialized parameters
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Mcl(_) => "mcl",
+            Algorithm::Mcode(_) => "mcode",
+            Algorithm::Louvain(_) => "louvain",
+            Algorithm::Leiden(_) => "leiden",
+            Algorithm::Hcca(_) => "hcca",
+            Algorithm::Dpclus(_) => "dpclus",
+            Algorithm::Ipca(_) => "ipca",
+            Algorithm::Coach(_) => "coach",
+            Algorithm::GraphEntropy(_) => "graph_entropy",
+        }
+    }
+}
+
+impl GraphClusterer for Algorithm {
+    type Params = Self;
+
+    fn params(&self) -> &Self::Params {
+        self
+    }
+
+    fn cluster<N, E, Ty, Ix>(&self, graph: &Graph<N, E, Ty, Ix>) -> Result<Clustering>
+    where
+        E: Float,
+        Ty: EdgeType,
+        Ix: IndexType
+    {
+        match *self {
+            Algorithm::Mcl(params) => Mcl(params).cluster(graph),
+            Algorithm::Mcode(params) => Mcode(params).cluster(graph),
+            Algorithm::Louvain(params) => Louvain(params).cluster(graph),
+            Algorithm::Leiden(params) => Leiden(params).cluster(graph),
+            Algorithm::Hcca(params) => Hcca(params).cluster(graph),
+            Algorithm::Dpclus(params) => Dpclus(params).cluster(graph),
+            Algorithm::Ipca(params) => Ipca(params).cluster(graph),
+            Algorithm::Coach(params) => Coach(params).cluster(graph),
+            Algorithm::GraphEntropy(params) => GraphEntropy(params).cluster(graph),
+        }
+    }
+}
+
+fn _to_f64<E: Float>(w: &E) -> f64 {
+    w.to_f64().unwrap_or(f64::NAN)
+}
+
+/// Graph with `f64` edge weights and zero node weights, as the protein complex algorithms expect
+fn _weighted<N, E, Ty, Ix>(graph: &Graph<N, E, Ty, Ix>) -> Graph<f64, f64, Ty, Ix>
+where
+    E: Float,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    graph.map(|_, _| 0., |_, w| _to_f64(w))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph_utils::bridged_cliques;
+    use petgraph::graph::{DiGraph, UnGraph};
+
+    #[test]
+    fn test_every_algorithm() {
+        let graph = bridged_cliques();
+        let algorithms = [
+            Algorithm::Mcl(MclParams::default()),
+            Algorithm::Mcode(McodeParams::default()),
+            Algorithm::Louvain(LouvainParams::default()),
+            Algorithm::Leiden(LeidenParams::default()),
+            Algorithm::Hcca(HccaParams::builder().step_size(1).min_cluster_size(3).max_cluster_size(5).build().unwrap()),
+            Algorithm::Dpclus(DpclusParams::default()),
+            Algorithm::Ipca(IpcaParams::default()),
+            Algorithm::Coach(CoachParams::default()),
+            Algorithm::GraphEntropy(GraphEntropyParams::default()),
+        ];
+
+        for algorithm in algorithms.iter() {
+            let clustering = algorithm.cluster(&graph).unwrap();
+            assert_eq!(clustering.node_count(), 9);
+
+            let mut clusters = Vec::from(clustering);
+            clusters.sort();
+            match algorithm {
+                // the partitions put the leaf 8 with the clique it hangs from
+                Algorithm::Mcl(_) | Algorithm::Louvain(_) | Algorithm::Leiden(_) | Algorithm::GraphEntropy(_) => {
+                    assert_eq!(clusters, vec![vec![0, 1, 2, 3, 4, 8], vec![5, 6, 7]], "{:?}", algorithm)
+                }
+                // the clusters of IPCA overlap on the 5-clique
+                Algorithm::Ipca(_) => assert_eq!(clusters, vec![vec![0, 1, 2, 3, 4], vec![0, 1, 2, 3, 4, 5], vec![0, 1, 2, 3, 4, 8], vec![5, 6, 7]]),
+                // the density based algorithms leave the leaf unassigned
+                _ => assert_eq!(clusters, vec![vec![0, 1, 2, 3, 4], vec![5, 6, 7]], "{:?}", algorithm),
+            }
+        }
+    }
+
+    #[test]
+    fn test_mcode() {
+        let clustering = Algorithm::Mcode(McodeParams::default()).cluster(&bridged_cliques()).unwrap();
+        assert_eq!(clustering, vec![vec![0, 1, 2, 3, 4], vec![5, 6, 7]]);
+        assert_eq!(clustering.scores(), Some(&[5., 3.][..]));
+        assert_eq!(clustering.unassigned(), vec![8]);
+    }
+
+    #[test]
+    fn test_mcl_directed() {
+        // the edge directions are ignored like the other algorithms do
+        let edges = [(0, 1, 1.), (1, 2, 1.), (2, 0, 1.), (2, 3, 1.), (3, 4, 1.), (4, 5, 1.), (5, 3, 1.)];
+        let directed = Mcl(MclParams::default()).cluster(&DiGraph::<(), f64>::from_edges(edges)).unwrap();
+        let undirected = Mcl(MclParams::default()).cluster(&UnGraph::<(), f64>::from_edges(edges)).unwrap();
+        assert_eq!(directed, undirected);
+        assert_eq!(directed, vec![vec![0, 1, 2], vec![3, 4, 5]]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_config() {
+        let algorithm: Algorithm = serde_json::from_str(r#"{"algorithm": "louvain", "resolution": 0.5}"#).unwrap();
+        assert_eq!(algorithm, Algorithm::Louvain(LouvainParams::builder().resolution(0.5).build().unwrap()));
+
+        let algorithm: Algorithm = serde_json::from_str(r#"{"algorithm": "graph_entropy"}"#).unwrap();
+        assert_eq!(algorithm, Algorithm::GraphEntropy(GraphEntropyParams::default()));
+        assert_eq!(algorithm.name(), "graph_entropy");
+
+        let json = serde_json::to_string(&Algorithm::Mcode(McodeParams::default())).unwrap();
+        assert_eq!(serde_json::from_str::<Algorithm>(&json).unwrap(), Algorithm::Mcode(McodeParams::default()));
+    }
+}
@@ -0,0 +1,321 @@
+/*!
+Clusters found by any algorithm of the crate.
+
+A [`Clustering`] keeps the clusters in the order the algorithm reported them, each with its nodes in ascending order,
+together with the number of nodes of the graph so that nodes outside every cluster are known.
+Clusters may overlap, and every cluster may carry a score given by the algorithm.
+The complexes found by MCODE, DPClus, IPCA, CoAch and graph entropy clustering convert with [`ToClustering`].
+
+```rust
+use graph_clustering_rs::clustering::Clustering;
+
+// node 2 is in both clusters, and node 5 in none
+let clustering = Clustering::new(6, vec![vec![2, 1, 0], vec![2, 3, 4]]).unwrap()
+    .with_scores(vec![3., 2.5])
+    .unwrap();
+
+assert_eq!(clustering.len(), 2);
+assert_eq!(clustering[0], [0, 1, 2]);
+assert_eq!(clustering.sizes(), vec![3, 3]);
+assert_eq!(clustering.score(1), Some(2.5));
+assert_eq!(clustering.clusters_of(2), &[0, 1]);
+assert!(clustering.is_overlapping());
+assert_eq!(clustering.unassigned(), vec![5]);
+
+// a partition converts to a membership vector and back
+let partition = Clustering::from_membership(&[0, 0, 1, 1, 2]);
+assert_eq!(partition.singletons(), vec![2]);
+assert_eq!(partition.membership(), Some(vec![0, 0, 1, 1, 2]));
+assert_eq!(Vec::<Vec<usize>>::from(partition.without_singletons()), vec![vec![0, 1], vec![2, 3]]);
+```
+*/
+
+use std::{collections::HashSet, ops::Index};
+
+use anyhow::{bail, ensure, Result};
+use petgraph::graph::{IndexType, NodeIndex};
+
+/// Clusters of the nodes `0..node_count`, possibly overlapping, with optional scores
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clustering {
+    clusters: Vec<Vec<usize>>,
+    scores: Option<Vec<f64>>,
+    /// Clusters of every node, in ascending order
+    memberships: Vec<Vec<usize>>,
+}
+
+impl Clustering {
+    /// Clusters of a graph of `node_count` nodes, members are sorted and deduplicated
+    pub fn new(node_count: usize, clusters: Vec<Vec<usize>>) -> Result<Self> {
+        if let Some(&node) = clusters.iter().flatten().find(|&&n| n >= node_count) {
+            bail!("node {} is out of range for {} nodes", node, node_count);
+        }
+        Ok(Self::_new(node_count, clusters))
+    }
+
+    /// Clustering of nodes known to be below `node_count`
+    pub(crate) fn _new(node_count: usize, mut clusters: Vec<Vec<usize>>) -> Self {
+        let mut memberships = vec![Vec::new(); node_count];
+        for (i, cluster) in clusters.iter_mut().enumerate() {
+            cluster.sort_unstable();
+            cluster.dedup();
+            cluster.iter().for_each(|&n| memberships[n].push(i));
+        }
+
+        Self { clusters, scores: None, memberships }
+    }
+
+    /// Partition in which node `i` belongs to the cluster `membership[i]`
+    ///
+    /// Cluster labels are used as cluster indices, so unused labels give empty clusters.
+    pub fn from_membership(membership: &[usize]) -> Self {
+        let n_clusters = membership.iter().max().map_or(0, |&c| c + 1);
+        let mut clusters = vec![Vec::new(); n_clusters];
+        for (node, &c) in membership.iter().enumerate() {
+            clusters[c].push(node);
+        }
+        Self::_new(membership.len(), clusters)
+    }
+
+    /// Attach a score to every cluster
+    pub fn with_scores(mut self, scores: Vec<f64>) -> Result<Self> {
+        ensure!(scores.len() == self.clusters.len(), "expected {} scores, found {}", self.clusters.len(), scores.len());
+        self.scores = Some(scores);
+        Ok(self)
+    }
+
+    /// Number of nodes of the clustered graph
+    pub fn node_count(&self) -> usize {
+        self.memberships.len()
+    }
+
+    /// Number of clusters
+    pub fn len(&self) -> usize {
+        self.clusters.len()
+    }
+
+    /// Whether there are no clusters
+    pub fn is_empty(&self) -> bool {
+        self.clusters.is_empty()
+    }
+
+    /// Sorted nodes of the cluster `i`
+    pub fn cluster(&self, i: usize) -> Option<&[usize]> {
+        self.clusters.get(i).map(|c| c.as_slice())
+    }
+
+    /// Sorted nodes of every cluster
+    pub fn clusters(&self) -> &[Vec<usize>] {
+        &self.clusters
+    }
+
+    /// Iterator over the sorted nodes of every cluster
+    pub fn iter(&self) -> std::slice::Iter<'_, Vec<usize>> {
+        self.clusters.iter()
+    }
+
+    /// Number of nodes of every cluster
+    pub fn sizes(&self) -> Vec<usize> {
+        self.clusters.iter().map(|c| c.len()).collect()
+    }
+
+    /// Score of the cluster `i`, `None` if the algorithm does not score clusters
+    pub fn score(&self, i: usize) -> Option<f64> {
+        self.scores.as_ref().and_then(|s| s.get(i).copied())
+    }
+
+    /// Score of every cluster, `None` if the algorithm does not score clusters
+    pub fn scores(&self) -> Option<&[f64]> {
+        self.scores.as_deref()
+    }
+
+    /// Indices of the clusters containing `node`, empty if it is unassigned or out of range
+    pub fn clusters_of(&self, node: usize) -> &[usize] {
+        self.memberships.get(node).map_or(&[], |m| m.as_slice())
+    }
+
+    /// Whether the nodes `a` and `b` are members of a common cluster
+    pub fn share_cluster(&self, a: usize, b: usize) -> bool {
+        let (ca, cb) = (self.clusters_of(a), self.clusters_of(b));
+        ca.iter().any(|c| cb.binary_search(c).is_ok())
+    }
+
+    /// Whether some node belongs to more than one cluster
+    pub fn is_overlapping(&self) -> bool {
+        self.memberships.iter().any(|m| m.len() > 1)
+    }
+
+    /// Nodes that belong to no cluster, in ascending order
+    pub fn unassigned(&self) -> Vec<usize> {
+        (0..self.node_count()).filter(|&n| self.memberships[n].is_empty()).collect()
+    }
+
+    /// Indices of the clusters of a single node
+    pub fn singletons(&self) -> Vec<usize> {
+        (0..self.len()).filter(|&i| self.clusters[i].len() == 1).collect()
+    }
+
+    /// Cluster index of every node, `None` unless every node belongs to exactly one cluster
+    pub fn membership(&self) -> Option<Vec<usize>> {
+        self.memberships.iter()
+            .map(|m| if m.len() == 1 { Some(m[0]) } else { None })
+            .collect()
+    }
+
+    /// Remove the clusters of a single node, their nodes become unassigned unless they are in another cluster
+    pub fn without_singletons(self) -> Self {
+        self.retain(|c| c.len() > 1)
+    }
+
+    /// Add a cluster of a single node for every unassigned node, with a score of 0 if the clusters are scored
+    pub fn with_unassigned_as_singletons(self) -> Self {
+        let unassigned = self.unassigned();
+        let Self { mut clusters, mut scores, memberships } = self;
+
+        if let Some(scores) = scores.as_mut() {
+            scores.extend(unassigned.iter().map(|_| 0.));
+        }
+        clusters.extend(unassigned.into_iter().map(|n| vec![n]));
+
+        let mut clustering = Self::_new(memberships.len(), clusters);
+        clustering.scores = scores;
+        clustering
+    }
+
+    /// Keep the clusters for which `f` returns `true`, with their scores
+    pub fn retain<F: Fn(&[usize]) -> bool>(self, f: F) -> Self {
+        let node_count = self.node_count();
+        let keep: Vec<bool> = self.clusters.iter().map(|c| f(c)).collect();
+
+        let clusters = self.clusters.into_iter().zip(keep.iter()).filter(|(_, &k)| k).map(|(c, _)| c).collect();
+        let scores = self.scores.map(|s| s.into_iter().zip(keep.iter()).filter(|(_, &k)| k).map(|(s, _)| s).collect());
+
+        let mut clustering = Self::_new(node_count, clusters);
+        clustering.scores = scores;
+        clustering
+    }
+}
+
+/// Conversion of the clusters found on a petgraph graph into a [`Clustering`]
+///
+/// ```rust
+/// use graph_clustering_rs::clustering::ToClustering;
+/// use graph_clustering_rs::dpclus::*;
+/// use petgraph::graph::UnGraph;
+///
+/// // a triangle and the isolated node 3
+/// let mut graph = UnGraph::<f64, f64>::from_edges(&[(0, 1, 1.), (0, 2, 1.), (1, 2, 1.)]);
+/// graph.add_node(0.);
+///
+/// let clustering = graph.dpclus(&DpclusParams::default()).unwrap().clustering(graph.node_count()).unwrap();
+/// assert_eq!(clustering, vec![vec![0, 1, 2]]);
+/// assert_eq!(clustering.unassigned(), vec![3]);
+/// ```
+pub trait ToClustering {
+    /// Clustering of a graph of `node_count` nodes, in the order of the clusters
+    fn clustering(&self, node_count: usize) -> Result<Clustering>;
+}
+
+impl<Ix: IndexType> ToClustering for [HashSet<NodeIndex<Ix>>] {
+    fn clustering(&self, node_count: usize) -> Result<Clustering> {
+        let clusters = self.iter()
+            .map(|c| c.iter().map(|n| n.index()).collect())
+            .collect();
+        Clustering::new(node_count, clusters)
+    }
+}
+
+/// Clusters of the nodes up to the largest node found
+impl From<Vec<Vec<usize>>> for Clustering {
+    fn from(clusters: Vec<Vec<usize>>) -> Self {
+        let node_count = clusters.iter().flatten().max().map_or(0, |&n| n + 1);
+        Self::_new(node_count, clusters)
+    }
+}
+
+impl From<Clustering> for Vec<Vec<usize>> {
+    fn from(clustering: Clustering) -> Self {
+        clustering.clusters
+    }
+}
+
+impl PartialEq<Vec<Vec<usize>>> for Clustering {
+    fn eq(&self, other: &Vec<Vec<usize>>) -> bool {
+        &self.clusters == other
+    }
+}
+
+impl Index<usize> for Clustering {
+    type Output = [usize];
+
+    fn index(&self, i: usize) -> &[usize] {
+        &self.clusters[i]
+    }
+}
+
+impl IntoIterator for Clustering {
+    type Item = Vec<usize>;
+    type IntoIter = std::vec::IntoIter<Vec<usize>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.clusters.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Clustering {
+    type Item = &'a Vec<usize>;
+    type IntoIter = std::slice::Iter<'a, Vec<usize>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.clusters.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let clustering = Clustering::new(4, vec![vec![3, 1, 1], vec![0]]).unwrap();
+        assert_eq!(clustering, vec![vec![1, 3], vec![0]]);
+        assert_eq!(clustering.membership(), None);
+        assert_eq!(clustering.unassigned(), vec![2]);
+        assert_eq!(clustering.clusters_of(10), &[] as &[usize]);
+        assert!(clustering.share_cluster(1, 3));
+        assert!(!clustering.share_cluster(0, 1));
+        assert!(!clustering.share_cluster(2, 2));
+
+        assert!(Clustering::new(3, vec![vec![0, 3]]).is_err());
+        assert!(Clustering::new(3, vec![vec![0]]).unwrap().with_scores(vec![1., 2.]).is_err());
+    }
+
+    #[test]
+    fn test_conversion() {
+        let clusters = vec![vec![0, 2], vec![1, 3, 4]];
+        let clustering = Clustering::from(clusters.clone());
+        assert_eq!(clustering.node_count(), 5);
+        assert_eq!(clustering.membership(), Some(vec![0, 1, 0, 1, 1]));
+        assert_eq!(Clustering::from_membership(&[0, 1, 0, 1, 1]), clustering);
+        assert_eq!(Vec::<Vec<usize>>::from(clustering), clusters);
+
+        assert!(Clustering::from(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_singletons() {
+        let clustering = Clustering::new(5, vec![vec![0, 1], vec![2]]).unwrap()
+            .with_scores(vec![2., 1.])
+            .unwrap();
+
+        let filled = clustering.clone().with_unassigned_as_singletons();
+        assert_eq!(filled, vec![vec![0, 1], vec![2], vec![3], vec![4]]);
+        assert_eq!(filled.scores(), Some(&[2., 1., 0., 0.][..]));
+        assert_eq!(filled.singletons(), vec![1, 2, 3]);
+
+        let filtered = filled.without_singletons();
+        assert_eq!(filtered, vec![vec![0, 1]]);
+        assert_eq!(filtered.scores(), Some(&[2.][..]));
+        assert_eq!(filtered.unassigned(), vec![2, 3, 4]);
+    }
+}
@@ -0,0 +1,297 @@
+/*!
+CoAch detects protein complexes made of a dense core and attachments around it. It was developed by Wu et al.,
+details of the algorithm can be found in [A core-attachment based method to detect protein complexes in PPI networks](https://doi.org/10.1186/1471-2105-9-169).
+
+The algorithm runs in three steps:
+
+1. core detection: in the neighborhood graph of every node with at least two neighbors and at least the average degree of its neighbors,
+   the nodes whose degree is below the average degree of the neighborhood graph are removed, and then the node of smallest degree is removed until the rest is at least as dense as `density_threshold`
+2. redundancy filtering: in order of decreasing size, a core is merged into an earlier core if their overlap score `|A ∩ B|^2 / (|A| |B|)` is at least `overlap_threshold`
+3. attachment: a node outside a core joins the complex as an attachment if it is adjacent to more than half of the core
+
+Densities use the edge weights of the graph, so an unweighted network should have every edge weight set to 1.
+
+# Example Usage
+
+```rust
+use graph_clustering_rs::coach::*;
+use petgraph::graph::{NodeIndex, UnGraph};
+
+// a 4-clique, and the node 4 adjacent to three of its nodes
+let graph = UnGraph::<f64, f64>::from_edges(&[
+    (0, 1, 1.), (0, 2, 1.), (0, 3, 1.), (1, 2, 1.), (1, 3, 1.), (2, 3, 1.),
+    (4, 0, 1.), (4, 1, 1.), (4, 2, 1.),
+    (5, 3, 1.),
+]);
+
+let params = CoachParams::builder().overlap_threshold(0.9).build().unwrap();
+let complexes = graph.coach(&params).unwrap();
+
+assert_eq!(complexes[0].role(NodeIndex::new(3)), Some(Role::Core));
+assert_eq!(complexes[0].role(NodeIndex::new(4)), Some(Role::Attachment));
+assert_eq!(complexes[0].role(NodeIndex::new(5)), None);
+```
+*/
+
+use std::{collections::HashSet, iter::Sum};
+
+use anyhow::Result;
+use num_traits::Float;
+use petgraph::{EdgeType, graph::{Graph, IndexType, NodeIndex}};
+
+use crate::clustering::{Clustering, ToClustering};
+use crate::graph_utils::{density, neighbors};
+
+pub mod params;
+
+pub use params::{CoachParams, CoachParamsBuilder};
+
+/// Role of a member of a complex
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Role {
+    Core,
+    Attachment,
+}
+
+/// Complex found by CoAch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoachComplex<Ix: IndexType> {
+    /// Dense core of the complex
+    pub core: HashSet<NodeIndex<Ix>>,
+    /// Nodes adjacent to more than half of the core
+    pub attachments: HashSet<NodeIndex<Ix>>,
+}
+
+impl<Ix: IndexType> CoachComplex<Ix> {
+    /// Role of `node` in the complex, `None` if it is not a member
+    pub fn role(&self, node: NodeIndex<Ix>) -> Option<Role> {
+        if self.core.contains(&node) {
+            Some(Role::Core)
+        } else if self.attachments.contains(&node) {
+            Some(Role::Attachment)
+        } else {
+            None
+        }
+    }
+
+    /// Members of the complex with their roles, in order of index
+    pub fn members(&self) -> Vec<(NodeIndex<Ix>, Role)> {
+        let mut members: Vec<(NodeIndex<Ix>, Role)> = self.core.iter().map(|&n| (n, Role::Core))
+            .chain(self.attachments.iter().map(|&n| (n, Role::Attachment)))
+            .collect();
+        members.sort();
+        members
+    }
+
+    /// Number of members
+    pub fn len(&self) -> usize {
+        self.core.len() + self.attachments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.core.is_empty() && self.attachments.is_empty()
+    }
+}
+
+/// Complexes with their cores and attachments
+impl<Ix: IndexType> ToClustering for [CoachComplex<Ix>] {
+    fn clustering(&self, node_count: usize) -> Result<Clustering> {
+        let clusters = self.iter()
+            .map(|c| c.core.iter().chain(c.attachments.iter()).map(|n| n.index()).collect())
+            .collect();
+        Clustering::new(node_count, clusters)
+    }
+}
+
+pub trait CoachExt<W, Ix: IndexType> {
+    /// Find complexes with CoAch, in order of decreasing core size
+    fn coach(&self, params: &CoachParams<W>) -> Result<Vec<CoachComplex<Ix>>>;
+}
+
+impl<W, Ty, Ix> CoachExt<W, Ix> for Graph<W, W, Ty, Ix>
+where
+    W: Float + Sum,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    fn coach(&self, params: &CoachParams<W>) -> Result<Vec<CoachComplex<Ix>>> {
+        params.validate()?;
+
+        let mut cores: Vec<Vec<NodeIndex<Ix>>> = self.node_indices()
+            .filter(|&v| _is_seed(v, self))
+            .filter_map(|v| _find_core(v, self, params))
+            .map(|core| {
+                let mut core: Vec<NodeIndex<Ix>> = core.into_iter().collect();
+                core.sort();
+                core
+            })
+            .collect();
+        cores.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+        cores.dedup();
+
+        // redundancy filtering
+        let mut merged: Vec<HashSet<NodeIndex<Ix>>> = Vec::new();
+        for core in cores {
+            let core: HashSet<NodeIndex<Ix>> = core.into_iter().collect();
+            match merged.iter_mut().find(|m| _overlap_score::<W, Ix>(m, &core) >= params.overlap_threshold) {
+                Some(m) => m.extend(core),
+                None => merged.push(core),
+            }
+        }
+
+        Ok(merged.into_iter()
+            .map(|core| {
+                let attachments = _attachments(&core, self);
+                CoachComplex { core, attachments }
+            })
+            .collect())
+    }
+}
+
+/// Whether the degree of `v` is at least the average degree of its neighbors, peripheral nodes seed no core
+fn _is_seed<W, Ty, Ix>(v: NodeIndex<Ix>, graph: &Graph<W, W, Ty, Ix>) -> bool
+where
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    let adjacent = neighbors(v, graph);
+    if adjacent.is_empty() {
+        return false;
+    }
+
+    let average = adjacent.iter().map(|&n| neighbors(n, graph).len()).sum::<usize>() as f64 / adjacent.len() as f64;
+    adjacent.len() as f64 >= average
+}
+
+/// Core in the neighborhood graph of `v`, `None` if it is too small or not dense enough
+fn _find_core<W, Ty, Ix>(v: NodeIndex<Ix>, graph: &Graph<W, W, Ty, Ix>, params: &CoachParams<W>) -> Option<HashSet<NodeIndex<Ix>>>
+where
+    W: Float + Sum,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    let neighbors = neighbors(v, graph);
+    if neighbors.len() < 2 {
+        return None;
+    }
+
+    let neighborhood: HashSet<NodeIndex<Ix>> = neighbors.into_iter().chain(std::iter::once(v)).collect();
+    let degrees = _degrees_within(&neighborhood, graph);
+    let average = degrees.iter().map(|&(_, d)| d).sum::<usize>() as f64 / degrees.len() as f64;
+
+    let mut core: HashSet<NodeIndex<Ix>> = degrees.into_iter()
+        .filter(|&(n, d)| n == v || d as f64 >= average)
+        .map(|(n, _)| n)
+        .collect();
+
+    while core.len() >= params.min_core_size && density(&core, graph) < params.density_threshold {
+        // the node of smallest degree, the largest index on ties, leaves the core
+        let removed = _degrees_within(&core, graph).into_iter()
+            .filter(|&(n, _)| n != v)
+            .min_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+            .map(|(n, _)| n)?;
+        core.remove(&removed);
+    }
+
+    if core.len() >= params.min_core_size {
+        Some(core)
+    } else {
+        None
+    }
+}
+
+/// Number of neighbors of every node inside `nodes`
+fn _degrees_within<W, Ty, Ix>(nodes: &HashSet<NodeIndex<Ix>>, graph: &Graph<W, W, Ty, Ix>) -> Vec<(NodeIndex<Ix>, usize)>
+where
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    nodes.iter()
+        .map(|&n| (n, neighbors(n, graph).intersection(nodes).count()))
+        .collect()
+}
+
+/// `|a ∩ b|^2 / (|a| |b|)`
+fn _overlap_score<W: Float, Ix: IndexType>(a: &HashSet<NodeIndex<Ix>>, b: &HashSet<NodeIndex<Ix>>) -> W {
+    let shared = W::from(a.intersection(b).count()).unwrap();
+    shared * shared / W::from(a.len() * b.len()).unwrap()
+}
+
+/// Nodes outside the core adjacent to more than half of it
+fn _attachments<W, Ty, Ix>(core: &HashSet<NodeIndex<Ix>>, graph: &Graph<W, W, Ty, Ix>) -> HashSet<NodeIndex<Ix>>
+where
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    core.iter()
+        .flat_map(|&n| neighbors(n, graph))
+        .filter(|n| !core.contains(n))
+        .collect::<HashSet<NodeIndex<Ix>>>()
+        .into_iter()
+        .filter(|&n| 2 * neighbors(n, graph).intersection(core).count() > core.len())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph_utils::node_set;
+    use petgraph::Undirected;
+
+    /// K5 on 0..=4 with the node 5 adjacent to 0, 1 and 2, and K3 on 6..=8 with the leaf 9 attached to 6,
+    /// joined by the edges 3-7 and 4-8
+    fn graph1() -> Graph::<f64, f64, Undirected, usize> {
+        Graph::<f64, f64, Undirected, usize>::from_edges([
+            (0, 1, 1.), (0, 2, 1.), (0, 3, 1.), (0, 4, 1.),
+            (1, 2, 1.), (1, 3, 1.), (1, 4, 1.),
+            (2, 3, 1.), (2, 4, 1.),
+            (3, 4, 1.),
+            (5, 0, 1.), (5, 1, 1.), (5, 2, 1.),
+            (6, 7, 1.), (6, 8, 1.), (7, 8, 1.),
+            (9, 6, 1.),
+            (3, 7, 1.), (4, 8, 1.),
+        ])
+    }
+
+    #[test]
+    fn test_find_core() {
+        let graph = graph1();
+        let params = CoachParams::default();
+        // 3 and 4 have fewer neighbors than the average in the neighborhood of 0
+        assert_eq!(_find_core(NodeIndex::new(0), &graph, &params), Some(node_set(&[0, 1, 2])));
+        assert_eq!(_find_core(NodeIndex::new(3), &graph, &params), Some(node_set(&[0, 1, 2, 3, 4])));
+        assert_eq!(_find_core(NodeIndex::new(6), &graph, &params), Some(node_set(&[6, 7, 8])));
+        assert_eq!(_find_core(NodeIndex::new(9), &graph, &params), None);
+    }
+
+    #[test]
+    fn test_seeds() {
+        let graph = graph1();
+        // 5 and 7 have fewer neighbors than their neighbors on average
+        let seeds: Vec<usize> = graph.node_indices().filter(|&v| _is_seed(v, &graph)).map(|v| v.index()).collect();
+        assert_eq!(seeds, vec![0, 1, 2, 3, 4, 6]);
+    }
+
+    #[test]
+    fn test_merged_cores() {
+        // the peripheral node 5 seeds no core of its own, so it stays an attachment
+        let complexes = graph1().coach(&CoachParams::default()).unwrap();
+        assert_eq!(complexes, vec![
+            CoachComplex { core: node_set(&[0, 1, 2, 3, 4]), attachments: node_set(&[5]) },
+            CoachComplex { core: node_set(&[6, 7, 8]), attachments: HashSet::new() },
+        ]);
+    }
+
+    #[test]
+    fn test_attachments() {
+        let params = CoachParams::builder().overlap_threshold(0.9).build().unwrap();
+        let complexes = graph1().coach(&params).unwrap();
+        assert_eq!(complexes, vec![
+            CoachComplex { core: node_set(&[0, 1, 2, 3, 4]), attachments: node_set(&[5]) },
+            CoachComplex { core: node_set(&[0, 1, 2]), attachments: node_set(&[3, 4, 5]) },
+            CoachComplex { core: node_set(&[6, 7, 8]), attachments: HashSet::new() },
+        ]);
+        assert_eq!(complexes[0].members().len(), 6);
+        assert_eq!(complexes[1].members()[3], (NodeIndex::new(3), Role::Attachment));
+    }
+}
@@ -0,0 +1,96 @@
+/*!
+Parameters of [`CoachExt::coach`](super::CoachExt::coach).
+
+The defaults are a core density of 0.7, an overlap score of 0.225 for redundant cores and cores of at least 3 nodes.
+
+```rust
+use graph_clustering_rs::coach::*;
+
+let params: CoachParams<f64> = CoachParams::builder()
+    .density_threshold(0.8)
+    .overlap_threshold(0.5)
+    .build()
+    .unwrap();
+assert_eq!(params.min_core_size, 3);
+
+assert!(CoachParams::<f64>::builder().min_core_size(1).build().is_err());
+```
+*/
+
+use anyhow::{ensure, Result};
+use num_traits::{Float, one, zero};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Parameters of CoAch
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, bound(deserialize = "W: Float + Deserialize<'de>")))]
+pub struct CoachParams<W> {
+    /// Minimum density of a core
+    pub density_threshold: W,
+    /// Cores whose overlap score is at least this value are merged
+    pub overlap_threshold: W,
+    /// Minimum number of nodes of a core
+    pub min_core_size: usize,
+}
+
+impl<W: Float> Default for CoachParams<W> {
+    fn default() -> Self {
+        Self {
+            density_threshold: W::from(0.7).unwrap(),
+            overlap_threshold: W::from(0.225).unwrap(),
+            min_core_size: 3,
+        }
+    }
+}
+
+impl<W: Float> CoachParams<W> {
+    /// Start building parameters from the defaults
+    pub fn builder() -> CoachParamsBuilder<W> {
+        CoachParamsBuilder {
+            params: Self::default(),
+        }
+    }
+
+    /// Check that the parameters can be used to run CoAch
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.density_threshold > zero() && self.density_threshold <= one(), "density_threshold must be in (0, 1]");
+        ensure!(self.overlap_threshold > zero() && self.overlap_threshold <= one(), "overlap_threshold must be in (0, 1]");
+        ensure!(self.min_core_size >= 2, "min_core_size must be at least 2");
+        Ok(())
+    }
+}
+
+/// Builder of [`CoachParams`]
+#[derive(Debug, Clone)]
+pub struct CoachParamsBuilder<W> {
+    params: CoachParams<W>,
+}
+
+impl<W: Float> CoachParamsBuilder<W> {
+    /// Set the minimum density of a core
+    pub fn density_threshold(mut self, density_threshold: W) -> Self {
+        self.params.density_threshold = density_threshold;
+        self
+    }
+
+    /// Set the overlap score from which cores are merged
+    pub fn overlap_threshold(mut self, overlap_threshold: W) -> Self {
+        self.params.overlap_threshold = overlap_threshold;
+        self
+    }
+
+    /// Set the minimum number of nodes of a core
+    pub fn min_core_size(mut self, min_core_size: usize) -> Self {
+        self.params.min_core_size = min_core_size;
+        self
+    }
+
+    /// Validate and return the parameters
+    pub fn build(self) -> Result<CoachParams<W>> {
+        self.params.validate()?;
+        Ok(self.params)
+    }
+}
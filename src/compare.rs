@@ -0,0 +1,415 @@
+/*!
+Comparison of two clusterings of the same nodes, such as a predicted clustering and a reference.
+
+| function | clusterings | range | identical |
+| -------- | ----------- | ----- | --------- |
+| [`nmi`] | partitions | `[0, 1]` | 1 |
+| [`overlapping_nmi`] | covers | `[0, 1]` | 1 |
+| [`adjusted_rand_index`] | partitions | `[-1, 1]`, 0 for random | 1 |
+| [`rand_index`] | partitions | `[0, 1]` | 1 |
+| [`variation_of_information`] | partitions | `[0, ln n]` | 0 |
+| [`jaccard_index`] | covers | `[0, 1]` | 1 |
+| [`f_measure`] | covers | `[0, 1]` | 1 |
+| [`omega_index`] | covers | `[-1, 1]`, 0 for random | 1 |
+| [`split_join_distance`] | partitions | `[0, 2n)` | 0 |
+| [`projection_distance`] | partitions | `[0, n)` | 0 |
+
+Both clusterings cover the nodes `0..n`, where `n` is the larger of their node counts.
+Functions on partitions fail if a clustering overlaps and treat every unassigned node as a cluster of its own.
+Functions on covers accept overlapping clusters: the pair counting indices count the pairs of nodes that share a cluster,
+and the Omega index compares how many clusters every pair shares. Entropies are in nats.
+
+```rust
+use graph_clustering_rs::clustering::Clustering;
+use graph_clustering_rs::compare::*;
+
+let predicted = Clustering::from_membership(&[0, 0, 0, 1, 1, 1]);
+let reference = Clustering::from_membership(&[0, 0, 1, 1, 2, 2]);
+
+assert_eq!(nmi(&predicted, &predicted).unwrap(), 1.);
+assert!((adjusted_rand_index(&predicted, &reference).unwrap() - 8. / 33.).abs() < 1e-12);
+assert_eq!(rand_index(&predicted, &reference).unwrap(), 2. / 3.);
+// two nodes move in one direction and one in the other, as clm dist reports
+assert_eq!(split_join_distance(&predicted, &reference).unwrap(), 3);
+assert_eq!(projection_distance(&predicted, &reference).unwrap(), 2);
+```
+*/
+
+use std::collections::HashMap;
+
+use anyhow::{ensure, Result};
+
+use crate::clustering::Clustering;
+
+/// Normalized mutual information of two partitions, normalized by the mean of their entropies
+pub fn nmi(a: &Clustering, b: &Clustering) -> Result<f64> {
+    let table = _Contingency::new(a, b)?;
+    let (ha, hb) = (_entropy(&table.a, table.n), _entropy(&table.b, table.n));
+    if ha + hb == 0. {
+        return Ok(1.);
+    }
+    Ok((2. * table.mutual_information() / (ha + hb)).clamp(0., 1.))
+}
+
+/// Normalized mutual information of two covers by Lancichinetti, Fortunato and Kertész
+///
+/// Every cluster is a binary random variable over the nodes, and its entropy conditioned on the other cover is the smallest
+/// conditional entropy given one of the other clusters, among those that are more alike than unlike it. The normalized
+/// conditional entropies are averaged over the clusters of both covers.
+pub fn overlapping_nmi(a: &Clustering, b: &Clustering) -> Result<f64> {
+    let n = a.node_count().max(b.node_count());
+    if n == 0 {
+        return Ok(1.);
+    }
+    let (ha, hb) = (_conditional_cover_entropy(a, b, n), _conditional_cover_entropy(b, a, n));
+    Ok((1. - (ha + hb) / 2.).clamp(0., 1.))
+}
+
+/// Adjusted Rand index of two partitions, 1 if both put every node alone or every node together
+pub fn adjusted_rand_index(a: &Clustering, b: &Clustering) -> Result<f64> {
+    let table = _Contingency::new(a, b)?;
+    let pairs = _pairs(table.n) as f64;
+    let together = table.pairs_both() as f64;
+    let (pa, pb) = (table.pairs_a() as f64, table.pairs_b() as f64);
+
+    let expected = if pairs > 0. { pa * pb / pairs } else { 0. };
+    let max = (pa + pb) / 2.;
+    if max == expected {
+        return Ok(1.);
+    }
+    Ok((together - expected) / (max - expected))
+}
+
+/// Rand index of two partitions, the fraction of the pairs of nodes on which they agree
+pub fn rand_index(a: &Clustering, b: &Clustering) -> Result<f64> {
+    let table = _Contingency::new(a, b)?;
+    let pairs = _pairs(table.n);
+    if pairs == 0 {
+        return Ok(1.);
+    }
+
+    let together = table.pairs_both();
+    let apart = pairs + together - table.pairs_a() - table.pairs_b();
+    Ok((together + apart) as f64 / pairs as f64)
+}
+
+/// Variation of information of two partitions, the entropy of each that the other does not explain
+pub fn variation_of_information(a: &Clustering, b: &Clustering) -> Result<f64> {
+    let table = _Contingency::new(a, b)?;
+    let vi = _entropy(&table.a, table.n) + _entropy(&table.b, table.n) - 2. * table.mutual_information();
+    Ok(vi.max(0.))
+}
+
+/// Jaccard index of the pairs of nodes that share a cluster, 1 if no pair does in either cover
+pub fn jaccard_index(a: &Clustering, b: &Clustering) -> Result<f64> {
+    let (pa, pb, both) = _pair_counts(a, b)?;
+    let either = pa + pb - both;
+    Ok(if either > 0 { both as f64 / either as f64 } else { 1. })
+}
+
+/// F-measure of the pairs of nodes that share a cluster, the harmonic mean of the pair precision and recall
+///
+/// The measure is symmetric, so either cover can be the reference. It is 1 if no pair shares a cluster in either cover.
+pub fn f_measure(a: &Clustering, b: &Clustering) -> Result<f64> {
+    let (pa, pb, both) = _pair_counts(a, b)?;
+    let total = pa + pb;
+    Ok(if total > 0 { 2. * both as f64 / total as f64 } else { 1. })
+}
+
+/// Omega index of Collins and Dent, the agreement on the number of clusters every pair of nodes shares corrected for chance
+///
+/// It is the adjusted Rand index for partitions.
+pub fn omega_index(a: &Clustering, b: &Clustering) -> Result<f64> {
+    let n = a.node_count().max(b.node_count());
+    let pairs = _pairs(n);
+    if pairs == 0 {
+        return Ok(1.);
+    }
+
+    let (agree, na, nb) = if !a.is_overlapping() && !b.is_overlapping() {
+        // every pair shares one cluster or none
+        let table = _Contingency::new(a, b)?;
+        let (pa, pb, both) = (table.pairs_a(), table.pairs_b(), table.pairs_both());
+        let counts = |p: usize| vec![(0, pairs - p), (1, p)].into_iter().collect::<HashMap<usize, usize>>();
+        (pairs + 2 * both - pa - pb, counts(pa), counts(pb))
+    } else {
+        let (pa, pb) = (_shared_pairs(a), _shared_pairs(b));
+
+        // pairs that share no cluster in either cover agree
+        let mut agree = pairs;
+        for (p, &t) in pa.iter() {
+            if pb.get(p) != Some(&t) {
+                agree -= 1;
+            }
+        }
+        agree -= pb.keys().filter(|p| !pa.contains_key(p)).count();
+        (agree, _counts_by_shared(&pa, pairs), _counts_by_shared(&pb, pairs))
+    };
+
+    let m = pairs as f64;
+    let observed = agree as f64 / m;
+    let expected: f64 = na.iter().map(|(t, &c)| c as f64 * nb.get(t).copied().unwrap_or(0) as f64).sum::<f64>() / (m * m);
+    if expected == 1. {
+        return Ok(1.);
+    }
+    Ok((observed - expected) / (1. - expected))
+}
+
+/// Split/join distance of van Dongen between two partitions, the number of nodes to move to turn either into the other
+///
+/// It is the sum of [`projection_distance`] in both directions, as reported by `clm dist`.
+pub fn split_join_distance(a: &Clustering, b: &Clustering) -> Result<usize> {
+    Ok(projection_distance(a, b)? + projection_distance(b, a)?)
+}
+
+/// Number of nodes of `a` outside the cluster of `b` that overlaps most with their cluster
+pub fn projection_distance(a: &Clustering, b: &Clustering) -> Result<usize> {
+    let table = _Contingency::new(a, b)?;
+    let mut best = vec![0; table.a.len()];
+    for (&(i, _), &c) in table.cells.iter() {
+        best[i] = best[i].max(c);
+    }
+    Ok(table.n - best.iter().sum::<usize>())
+}
+
+/// Contingency table of two partitions, unassigned nodes are clusters of their own
+struct _Contingency {
+    n: usize,
+    /// Number of nodes of every cluster of the partitions
+    a: Vec<usize>,
+    b: Vec<usize>,
+    /// Number of nodes in both clusters for every pair of clusters that intersect
+    cells: HashMap<(usize, usize), usize>,
+}
+
+impl _Contingency {
+    fn new(a: &Clustering, b: &Clustering) -> Result<Self> {
+        let n = a.node_count().max(b.node_count());
+        let (ma, mb) = (_membership(a, n)?, _membership(b, n)?);
+
+        let mut sizes_a = Vec::new();
+        let mut sizes_b = Vec::new();
+        let mut cells = HashMap::new();
+        for (&i, &j) in ma.iter().zip(mb.iter()) {
+            _increment(&mut sizes_a, i);
+            _increment(&mut sizes_b, j);
+            *cells.entry((i, j)).or_insert(0) += 1;
+        }
+
+        Ok(Self { n, a: sizes_a, b: sizes_b, cells })
+    }
+
+    fn mutual_information(&self) -> f64 {
+        let n = self.n as f64;
+        self.cells.iter()
+            .map(|(&(i, j), &c)| {
+                let c = c as f64;
+                c / n * (c * n / (self.a[i] as f64 * self.b[j] as f64)).ln()
+            })
+            .sum()
+    }
+
+    fn pairs_a(&self) -> usize {
+        self.a.iter().map(|&s| _pairs(s)).sum()
+    }
+
+    fn pairs_b(&self) -> usize {
+        self.b.iter().map(|&s| _pairs(s)).sum()
+    }
+
+    /// Number of pairs of nodes together in both partitions
+    fn pairs_both(&self) -> usize {
+        self.cells.values().map(|&c| _pairs(c)).sum()
+    }
+}
+
+/// Cluster of every node of a partition, unassigned nodes get new clusters after the existing ones
+fn _membership(clustering: &Clustering, n: usize) -> Result<Vec<usize>> {
+    ensure!(!clustering.is_overlapping(), "expected a partition, but the clusters overlap");
+
+    let mut next = clustering.len();
+    Ok((0..n)
+        .map(|node| match clustering.clusters_of(node).first() {
+            Some(&c) => c,
+            None => {
+                next += 1;
+                next - 1
+            }
+        })
+        .collect())
+}
+
+fn _increment(counts: &mut Vec<usize>, i: usize) {
+    if counts.len() <= i {
+        counts.resize(i + 1, 0);
+    }
+    counts[i] += 1;
+}
+
+fn _pairs(n: usize) -> usize {
+    n * n.saturating_sub(1) / 2
+}
+
+/// Entropy of the cluster sizes, in nats
+fn _entropy(sizes: &[usize], n: usize) -> f64 {
+    let n = n as f64;
+    sizes.iter()
+        .filter(|&&s| s > 0)
+        .map(|&s| {
+            let p = s as f64 / n;
+            -p * p.ln()
+        })
+        .sum()
+}
+
+/// Number of pairs of nodes that share a cluster in `a`, in `b` and in both
+///
+/// Partitions are counted from their contingency table, and only covers list their pairs.
+fn _pair_counts(a: &Clustering, b: &Clustering) -> Result<(usize, usize, usize)> {
+    if !a.is_overlapping() && !b.is_overlapping() {
+        let table = _Contingency::new(a, b)?;
+        return Ok((table.pairs_a(), table.pairs_b(), table.pairs_both()));
+    }
+
+    let (pa, pb) = (_shared_pairs(a), _shared_pairs(b));
+    let both = pa.keys().filter(|p| pb.contains_key(p)).count();
+    Ok((pa.len(), pb.len(), both))
+}
+
+/// Number of shared clusters of every pair of nodes that shares at least one
+fn _shared_pairs(clustering: &Clustering) -> HashMap<(usize, usize), usize> {
+    let mut pairs = HashMap::new();
+    for cluster in clustering.iter() {
+        for (k, &i) in cluster.iter().enumerate() {
+            for &j in cluster[k + 1..].iter() {
+                *pairs.entry((i, j)).or_insert(0) += 1;
+            }
+        }
+    }
+    pairs
+}
+
+/// Number of pairs sharing every number of clusters, zero included
+fn _counts_by_shared(shared: &HashMap<(usize, usize), usize>, pairs: usize) -> HashMap<usize, usize> {
+    let mut counts = HashMap::new();
+    counts.insert(0, pairs - shared.len());
+    for &t in shared.values() {
+        *counts.entry(t).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// `h(p) = -p log p`, 0 at 0
+fn _h(p: f64) -> f64 {
+    if p > 0. { -p * p.ln() } else { 0. }
+}
+
+/// Mean entropy of the clusters of `x` conditioned on the cover `y`, normalized by the entropy of every cluster
+fn _conditional_cover_entropy(x: &Clustering, y: &Clustering, n: usize) -> f64 {
+    let n = n as f64;
+    let binary = |size: usize| {
+        let p = size as f64 / n;
+        _h(p) + _h(1. - p)
+    };
+
+    let mut sum = 0.;
+    let mut count = 0;
+    for cx in x.iter() {
+        // empty clusters and clusters of every node carry no information
+        let hx = binary(cx.len());
+        if hx == 0. {
+            continue;
+        }
+        count += 1;
+
+        let mut best = hx;
+        for cy in y.iter() {
+            let both = cx.iter().filter(|i| cy.binary_search(i).is_ok()).count() as f64;
+            let p11 = both / n;
+            let p10 = (cx.len() as f64 - both) / n;
+            let p01 = (cy.len() as f64 - both) / n;
+            let p00 = 1. - p11 - p10 - p01;
+            // clusters closer to the complement of cx do not explain it
+            if _h(p11) + _h(p00) < _h(p01) + _h(p10) {
+                continue;
+            }
+            let conditional = _h(p11) + _h(p10) + _h(p01) + _h(p00) - binary(cy.len());
+            best = best.min(conditional);
+        }
+        sum += best / hx;
+    }
+
+    if count > 0 { sum / count as f64 } else { 0. }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn partitions() -> (Clustering, Clustering) {
+        (Clustering::from_membership(&[0, 0, 0, 1, 1, 1]), Clustering::from_membership(&[0, 0, 1, 1, 2, 2]))
+    }
+
+    #[test]
+    fn test_partitions() {
+        let (a, b) = partitions();
+
+        assert_abs_diff_eq!(nmi(&a, &b).unwrap(), 0.5158037429793888, epsilon = 1e-12);
+        assert_abs_diff_eq!(variation_of_information(&a, &b).unwrap(), 0.8675632284814613, epsilon = 1e-12);
+        assert_abs_diff_eq!(adjusted_rand_index(&a, &b).unwrap(), 8. / 33., epsilon = 1e-12);
+        assert_abs_diff_eq!(rand_index(&a, &b).unwrap(), 2. / 3., epsilon = 1e-12);
+        assert_abs_diff_eq!(jaccard_index(&a, &b).unwrap(), 2. / 7., epsilon = 1e-12);
+        assert_abs_diff_eq!(f_measure(&a, &b).unwrap(), 4. / 9., epsilon = 1e-12);
+        assert_eq!(projection_distance(&a, &b).unwrap(), 2);
+        assert_eq!(projection_distance(&b, &a).unwrap(), 1);
+        assert_eq!(split_join_distance(&a, &b).unwrap(), 3);
+
+        // the contingency table counts the same pairs as the pair lists of covers, unassigned nodes sharing no cluster
+        let partial = Clustering::new(6, vec![vec![0, 1, 2], vec![3, 4]]).unwrap();
+        for (x, y) in [(&a, &b), (&partial, &b), (&b, &partial)] {
+            let (px, py) = (_shared_pairs(x), _shared_pairs(y));
+            let both = px.keys().filter(|p| py.contains_key(p)).count();
+            assert_eq!(_pair_counts(x, y).unwrap(), (px.len(), py.len(), both));
+        }
+
+        // the overlapping variants agree on the identity
+        assert_abs_diff_eq!(omega_index(&a, &a).unwrap(), 1., epsilon = 1e-12);
+        assert_abs_diff_eq!(overlapping_nmi(&b, &b).unwrap(), 1., epsilon = 1e-12);
+        assert_abs_diff_eq!(omega_index(&a, &b).unwrap(), adjusted_rand_index(&a, &b).unwrap(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_identical() {
+        let (a, _) = partitions();
+        assert_eq!(nmi(&a, &a).unwrap(), 1.);
+        assert_eq!(adjusted_rand_index(&a, &a).unwrap(), 1.);
+        assert_eq!(rand_index(&a, &a).unwrap(), 1.);
+        assert_eq!(variation_of_information(&a, &a).unwrap(), 0.);
+        assert_eq!(split_join_distance(&a, &a).unwrap(), 0);
+
+        // a single cluster and all singletons share no information
+        let one = Clustering::from_membership(&[0; 6]);
+        let singletons = Clustering::new(6, Vec::new()).unwrap();
+        assert_eq!(nmi(&one, &singletons).unwrap(), 0.);
+        assert_eq!(adjusted_rand_index(&one, &singletons).unwrap(), 0.);
+        assert_eq!(jaccard_index(&one, &singletons).unwrap(), 0.);
+        assert_eq!(nmi(&one, &one).unwrap(), 1.);
+        assert_eq!(adjusted_rand_index(&singletons, &singletons).unwrap(), 1.);
+    }
+
+    #[test]
+    fn test_covers() {
+        let a = Clustering::new(6, vec![vec![0, 1, 2, 3], vec![3, 4, 5]]).unwrap();
+        let b = Clustering::new(6, vec![vec![0, 1, 2], vec![2, 3, 4, 5]]).unwrap();
+
+        assert_abs_diff_eq!(omega_index(&a, &b).unwrap(), 4. / 9., epsilon = 1e-12);
+        assert_abs_diff_eq!(overlapping_nmi(&a, &b).unwrap(), 0.4795739585136226, epsilon = 1e-12);
+        assert_abs_diff_eq!(overlapping_nmi(&a, &b).unwrap(), overlapping_nmi(&b, &a).unwrap(), epsilon = 1e-12);
+        assert_eq!(omega_index(&a, &a).unwrap(), 1.);
+        assert_abs_diff_eq!(jaccard_index(&a, &b).unwrap(), 7. / 11., epsilon = 1e-12);
+
+        assert!(nmi(&a, &b).is_err());
+        assert!(split_join_distance(&a, &b).is_err());
+    }
+}
@@ -0,0 +1,254 @@
+/*!
+DPClus finds clusters by tracking the density and the periphery of clusters grown from seeds. It was developed by Altaf-Ul-Amin et al.,
+details of the algorithm can be found in [Development and implementation of an algorithm for detection of protein complexes in large interaction networks](https://doi.org/10.1186/1471-2105-7-207).
+
+The priority of an edge is the number of common neighbors of its ends, or its weight for weighted graphs, and the priority of a node is
+the sum of the priorities of its edges. The node with the highest priority seeds a cluster, which repeatedly takes the neighbor with
+the highest priority to the cluster as long as
+
+- the density of the cluster with the neighbor is at least `d_in`, and
+- the cluster property of the neighbor, the weight of its edges to the cluster divided by the density and the size of the cluster, is at least `cp_in`.
+
+In the non-overlapping mode the nodes of a cluster are removed from the graph, and in the overlapping mode only the edges inside the cluster are.
+Priorities are computed again on the remaining graph for every cluster, until no edge is left.
+
+Densities use the edge weights of the graph, so an unweighted network should have every edge weight set to 1.
+
+# Example Usage
+
+```rust
+use graph_clustering_rs::dpclus::*;
+use petgraph::graph::{NodeIndex, UnGraph};
+
+// two 4-cliques sharing the node 3
+let graph = UnGraph::<f64, f64>::from_edges(&[
+    (0, 1, 1.), (0, 2, 1.), (0, 3, 1.), (1, 2, 1.), (1, 3, 1.), (2, 3, 1.),
+    (3, 4, 1.), (3, 5, 1.), (3, 6, 1.), (4, 5, 1.), (4, 6, 1.), (5, 6, 1.),
+]);
+let shared = NodeIndex::new(3);
+
+// the node 3 is removed with the first cluster, so the second one is only a triangle
+let clusters = graph.dpclus(&DpclusParams::default()).unwrap();
+assert_eq!(clusters.len(), 2);
+assert!(clusters[0].contains(&shared) && !clusters[1].contains(&shared));
+assert_eq!(clusters[1].len(), 3);
+
+// in the overlapping mode only the edges of the first cluster are removed, and both clusters keep the node 3
+let params = DpclusParams::builder().overlapping(true).build().unwrap();
+let clusters = graph.dpclus(&params).unwrap();
+assert!(clusters.iter().all(|c| c.len() == 4 && c.contains(&shared)));
+```
+*/
+
+use std::{cmp::Ordering, collections::HashSet, iter::Sum};
+
+use anyhow::Result;
+use num_traits::{Float, one, zero};
+use petgraph::{EdgeType, graph::{EdgeIndex, Graph, IndexType, NodeIndex}};
+
+use crate::graph_utils::{density, neighbors, priority_graph};
+
+pub mod params;
+
+pub use params::{DpclusParams, DpclusParamsBuilder};
+
+pub trait DpclusExt<W, Ix> {
+    /// Find clusters with DPClus, in the order they were found
+    ///
+    /// Clusters of a single node are not reported.
+    fn dpclus(&self, params: &DpclusParams<W>) -> Result<Vec<HashSet<NodeIndex<Ix>>>>;
+}
+
+impl<W, Ty, Ix> DpclusExt<W, Ix> for Graph<W, W, Ty, Ix>
+where
+    W: Float + Sum,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    fn dpclus(&self, params: &DpclusParams<W>) -> Result<Vec<HashSet<NodeIndex<Ix>>>> {
+        params.validate()?;
+
+        let mut working = self.clone();
+        // nodes that can still seed a cluster
+        let mut seeds: HashSet<NodeIndex<Ix>> = self.node_indices().collect();
+        let mut clusters = Vec::new();
+
+        loop {
+            let priority = priority_graph(&working, params.weighted);
+            let seed = match _select_seed(&priority, &seeds) {
+                Some(seed) => seed,
+                None => break,
+            };
+
+            let cluster = _grow_cluster(seed, &working, &priority, params);
+
+            let removed: Vec<EdgeIndex<Ix>> = if cluster.len() == 1 {
+                seeds.remove(&seed);
+                if params.overlapping { Vec::new() } else { _edges_where(&working, |a, b| a == seed || b == seed) }
+            } else if params.overlapping {
+                _edges_where(&working, |a, b| cluster.contains(&a) && cluster.contains(&b))
+            } else {
+                cluster.iter().for_each(|n| { seeds.remove(n); });
+                _edges_where(&working, |a, b| cluster.contains(&a) || cluster.contains(&b))
+            };
+            _remove_edges(&mut working, removed);
+
+            if cluster.len() > 1 {
+                clusters.push(cluster);
+            }
+        }
+
+        Ok(clusters)
+    }
+}
+
+/// Edges whose ends satisfy `f`
+fn _edges_where<W, Ty, Ix, F>(graph: &Graph<W, W, Ty, Ix>, f: F) -> Vec<EdgeIndex<Ix>>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    F: Fn(NodeIndex<Ix>, NodeIndex<Ix>) -> bool
+{
+    graph.edge_indices()
+        .filter(|&e| graph.edge_endpoints(e).is_some_and(|(a, b)| f(a, b)))
+        .collect()
+}
+
+/// Remove edges without moving the indices of the nodes
+fn _remove_edges<W, Ty: EdgeType, Ix: IndexType>(graph: &mut Graph<W, W, Ty, Ix>, mut edges: Vec<EdgeIndex<Ix>>) {
+    // removing an edge moves the last edge into its index, so the largest indices go first
+    edges.sort();
+    for e in edges.into_iter().rev() {
+        graph.remove_edge(e);
+    }
+}
+
+/// Node with edges and the highest priority, or the highest degree if every priority is zero
+fn _select_seed<W, Ty, Ix>(priority: &Graph<W, W, Ty, Ix>, seeds: &HashSet<NodeIndex<Ix>>) -> Option<NodeIndex<Ix>>
+where
+    W: Float + Sum,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    let candidates: Vec<(NodeIndex<Ix>, W, usize)> = priority.node_indices()
+        .filter(|n| seeds.contains(n))
+        .map(|n| (n, priority[n], neighbors(n, priority).len()))
+        .filter(|&(_, _, degree)| degree > 0)
+        .collect();
+
+    let all_zero = candidates.iter().all(|&(_, w, _)| w == zero());
+    candidates.into_iter()
+        .min_by(|a, b| {
+            let by_priority = if all_zero { b.2.cmp(&a.2) } else { b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal) };
+            by_priority.then(a.0.cmp(&b.0))
+        })
+        .map(|(n, _, _)| n)
+}
+
+/// Grow a cluster from `seed` with the neighbor of highest priority until the density or the cluster property gets too small
+fn _grow_cluster<W, Ty, Ix>(seed: NodeIndex<Ix>, graph: &Graph<W, W, Ty, Ix>, priority: &Graph<W, W, Ty, Ix>, params: &DpclusParams<W>) -> HashSet<NodeIndex<Ix>>
+where
+    W: Float + Sum,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    let mut cluster: HashSet<NodeIndex<Ix>> = std::iter::once(seed).collect();
+
+    loop {
+        let neighbors: HashSet<NodeIndex<Ix>> = cluster.iter()
+            .flat_map(|&n| neighbors(n, graph))
+            .filter(|n| !cluster.contains(n))
+            .collect();
+
+        // highest priority to the cluster, then highest node priority, then smallest index
+        let best = neighbors.into_iter()
+            .map(|n| (n, _weight_to(n, &cluster, priority), priority[n]))
+            .min_by(|a, b| {
+                b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal)
+                    .then(b.2.partial_cmp(&a.2).unwrap_or(Ordering::Equal))
+                    .then(a.0.cmp(&b.0))
+            });
+        let node = match best {
+            Some((node, _, _)) => node,
+            None => break,
+        };
+
+        // the cluster property of a single node cluster is not defined, so the first neighbor only needs the density
+        let denominator = density(&cluster, graph) * W::from(cluster.len()).unwrap();
+        let cp = if denominator > zero() { _weight_to(node, &cluster, graph) / denominator } else { one() };
+
+        let mut grown = cluster.clone();
+        grown.insert(node);
+
+        if density(&grown, graph) >= params.d_in && cp >= params.cp_in {
+            cluster = grown;
+        } else {
+            break;
+        }
+    }
+
+    cluster
+}
+
+/// Sum of the weights of the edges between `node` and the cluster
+fn _weight_to<W, Ty, Ix>(node: NodeIndex<Ix>, cluster: &HashSet<NodeIndex<Ix>>, graph: &Graph<W, W, Ty, Ix>) -> W
+where
+    W: Float + Sum,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    cluster.iter()
+        .filter_map(|&c| graph.find_edge_undirected(node, c))
+        .map(|(e, _)| graph[e])
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph_utils::{bridged_cliques, node_set};
+    use petgraph::Undirected;
+
+    #[test]
+    fn test_non_overlapping() {
+        let clusters = bridged_cliques().dpclus(&DpclusParams::default()).unwrap();
+        assert_eq!(clusters, vec![node_set(&[0, 1, 2, 3, 4]), node_set(&[5, 6, 7])]);
+    }
+
+    #[test]
+    fn test_cluster_property() {
+        // 5 passes the density but not the cluster property
+        let params = DpclusParams::builder().d_in(0.7).build().unwrap();
+        let clusters = bridged_cliques().dpclus(&params).unwrap();
+        assert_eq!(clusters, vec![node_set(&[0, 1, 2, 3, 4]), node_set(&[5, 6, 7])]);
+
+        let params = DpclusParams::builder().d_in(0.7).cp_in(0.1).build().unwrap();
+        let clusters = bridged_cliques().dpclus(&params).unwrap();
+        assert_eq!(clusters, vec![node_set(&[0, 1, 2, 3, 4, 5]), node_set(&[6, 7])]);
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let params = DpclusParams::builder().overlapping(true).build().unwrap();
+        let clusters = bridged_cliques().dpclus(&params).unwrap();
+        assert_eq!(clusters, vec![
+            node_set(&[0, 1, 2, 3, 4]),
+            node_set(&[5, 6, 7]),
+            node_set(&[0, 8]),
+            node_set(&[4, 5]),
+        ]);
+    }
+
+    #[test]
+    fn test_weighted() {
+        // the heavy triangle seeds the first cluster when the weights are the priorities
+        let graph = Graph::<f64, f64, Undirected, usize>::from_edges([
+            (0, 1, 0.2), (0, 2, 0.2), (1, 2, 0.2),
+            (3, 4, 1.), (3, 5, 1.), (4, 5, 1.),
+            (2, 3, 0.1),
+        ]);
+        let params = DpclusParams::builder().d_in(0.15).weighted(true).build().unwrap();
+        let clusters = graph.dpclus(&params).unwrap();
+        assert_eq!(clusters, vec![node_set(&[3, 4, 5]), node_set(&[0, 1, 2])]);
+    }
+}
@@ -0,0 +1,104 @@
+/*!
+Parameters of [`DpclusExt::dpclus`](super::DpclusExt::dpclus).
+
+The defaults follow the original publication: a minimum density of 0.9 and a minimum cluster property of 0.5 in the non-overlapping mode.
+
+```rust
+use graph_clustering_rs::dpclus::*;
+
+let params: DpclusParams<f64> = DpclusParams::builder()
+    .d_in(0.7)
+    .overlapping(true)
+    .build()
+    .unwrap();
+assert_eq!(params.cp_in, 0.5);
+
+assert!(DpclusParams::<f64>::builder().d_in(0.).build().is_err());
+```
+*/
+
+use anyhow::{ensure, Result};
+use num_traits::{Float, one, zero};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Parameters of DPClus
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, bound(deserialize = "W: Float + Deserialize<'de>")))]
+pub struct DpclusParams<W> {
+    /// Minimum density of a cluster
+    pub d_in: W,
+    /// Minimum cluster property of a node joining a cluster
+    pub cp_in: W,
+    /// Remove only the edges inside a found cluster, so that its nodes can also join later clusters
+    pub overlapping: bool,
+    /// Use the edge weights of the graph as priorities instead of the number of common neighbors
+    pub weighted: bool,
+}
+
+impl<W: Float> Default for DpclusParams<W> {
+    fn default() -> Self {
+        Self {
+            d_in: W::from(0.9).unwrap(),
+            cp_in: W::from(0.5).unwrap(),
+            overlapping: false,
+            weighted: false,
+        }
+    }
+}
+
+impl<W: Float> DpclusParams<W> {
+    /// Start building parameters from the defaults
+    pub fn builder() -> DpclusParamsBuilder<W> {
+        DpclusParamsBuilder {
+            params: Self::default(),
+        }
+    }
+
+    /// Check that the parameters can be used to run DPClus
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.d_in > zero() && self.d_in <= one(), "d_in must be in (0, 1]");
+        ensure!(self.cp_in > zero() && self.cp_in <= one(), "cp_in must be in (0, 1]");
+        Ok(())
+    }
+}
+
+/// Builder of [`DpclusParams`]
+#[derive(Debug, Clone)]
+pub struct DpclusParamsBuilder<W> {
+    params: DpclusParams<W>,
+}
+
+impl<W: Float> DpclusParamsBuilder<W> {
+    /// Set the minimum density of a cluster
+    pub fn d_in(mut self, d_in: W) -> Self {
+        self.params.d_in = d_in;
+        self
+    }
+
+    /// Set the minimum cluster property of a node joining a cluster
+    pub fn cp_in(mut self, cp_in: W) -> Self {
+        self.params.cp_in = cp_in;
+        self
+    }
+
+    /// Let the nodes of a found cluster join later clusters
+    pub fn overlapping(mut self, overlapping: bool) -> Self {
+        self.params.overlapping = overlapping;
+        self
+    }
+
+    /// Use the edge weights as priorities
+    pub fn weighted(mut self, weighted: bool) -> Self {
+        self.params.weighted = weighted;
+        self
+    }
+
+    /// Validate and return the parameters
+    pub fn build(self) -> Result<DpclusParams<W>> {
+        self.params.validate()?;
+        Ok(self.params)
+    }
+}
@@ -0,0 +1,289 @@
+/*!
+Graph entropy clustering grows possibly overlapping clusters from seed vertices while lowering the entropy of the graph.
+It was developed by Kenley and Cho, details of the algorithm can be found in
+[Entropy-based graph clustering: Application to biological and social networks](https://doi.org/10.1109/ICDM.2011.64).
+
+For a cluster `C`, the inner link probability `p(v)` of a vertex `v` is the fraction of the links of `v` that lead into `C`, and the entropy of `v` is
+`-p(v) log2 p(v) - (1 - p(v)) log2 (1 - p(v))`. The graph entropy is the sum of the entropies of all vertices, which is low when
+the neighbors of every vertex lie either all inside or all outside of the cluster.
+
+Starting from a seed vertex, a cluster is found in two steps:
+
+1. the cluster starts as the seed and its neighbors, and every neighbor whose removal lowers the graph entropy is removed
+2. the vertices on the outer boundary of the cluster are added as long as one of them lowers the graph entropy
+
+The vertices of the cluster can no longer become seeds, and seeds are chosen until every vertex is clustered, either the vertex of highest degree
+or a random vertex when `seed` is set. The graph is never modified, so clusters may overlap.
+
+Edge directions are ignored. [`GraphEntropyExt::graph_entropy`] counts every edge once, and [`GraphEntropyExt::graph_entropy_weighted`] uses
+the weight returned by a closure for every edge as the strength of the link.
+
+# Example Usage
+
+```rust
+use graph_clustering_rs::graph_entropy::*;
+use petgraph::graph::{NodeIndex, UnGraph};
+
+// two triangles, and the node 6 linked to 0 with weight 1 and to 3 with weight 3
+let graph = UnGraph::<(), f64>::from_edges(&[
+    (0, 1, 1.), (0, 2, 1.), (1, 2, 1.),
+    (3, 4, 1.), (3, 5, 1.), (4, 5, 1.),
+    (6, 0, 1.), (6, 3, 3.),
+]);
+let node = NodeIndex::new(6);
+
+// counted once, the edges of 6 tie it to both triangles, so the clusters overlap
+let clusters = graph.graph_entropy(&GraphEntropyParams::default()).unwrap();
+assert_eq!(clusters.len(), 2);
+assert!(clusters.iter().all(|c| c.len() == 4 && c.contains(&node)));
+
+// with the edge weights, 6 only belongs to the triangle it is tied to more strongly
+let weighted = graph.graph_entropy_weighted(&GraphEntropyParams::default(), |&w| w).unwrap();
+assert_eq!(weighted.iter().filter(|c| c.contains(&node)).count(), 1);
+assert!(weighted.iter().any(|c| c.contains(&node) && c.contains(&NodeIndex::new(3))));
+```
+*/
+
+use std::collections::{BTreeSet, HashSet};
+
+use anyhow::Result;
+use petgraph::{EdgeType, graph::{Graph, IndexType, NodeIndex}, visit::EdgeRef};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::network::Network;
+
+pub mod params;
+
+pub use params::{GraphEntropyParams, GraphEntropyParamsBuilder};
+
+pub trait GraphEntropyExt<E, Ix> {
+    /// Find clusters minimizing the graph entropy, in the order they were found, with every edge counted once
+    ///
+    /// Clusters smaller than `min_cluster_size` are not reported.
+    fn graph_entropy(&self, params: &GraphEntropyParams) -> Result<Vec<HashSet<NodeIndex<Ix>>>>;
+
+    /// Find clusters minimizing the graph entropy, in the order they were found, with the edge weights given by `weight`
+    ///
+    /// Weights must be finite and not negative.
+    fn graph_entropy_weighted<F: Fn(&E) -> f64>(&self, params: &GraphEntropyParams, weight: F) -> Result<Vec<HashSet<NodeIndex<Ix>>>>;
+}
+
+impl<N, E, Ty, Ix> GraphEntropyExt<E, Ix> for Graph<N, E, Ty, Ix>
+where
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    fn graph_entropy(&self, params: &GraphEntropyParams) -> Result<Vec<HashSet<NodeIndex<Ix>>>> {
+        self.graph_entropy_weighted(params, |_| 1.)
+    }
+
+    fn graph_entropy_weighted<F: Fn(&E) -> f64>(&self, params: &GraphEntropyParams, weight: F) -> Result<Vec<HashSet<NodeIndex<Ix>>>> {
+        params.validate()?;
+
+        let edges = self.edge_references().map(|e| (e.source().index(), e.target().index(), weight(e.weight())));
+        let network = Network::from_edges(self.node_count(), edges)?;
+
+        let mut rng = params.seed.map(StdRng::seed_from_u64);
+        let mut candidates: BTreeSet<usize> = (0..network.node_count()).collect();
+        let mut clusters = Vec::new();
+
+        while let Some(seed) = _select_seed(&candidates, &network, &mut rng) {
+            let cluster = _grow_cluster(seed, &network);
+            cluster.iter().for_each(|v| { candidates.remove(v); });
+
+            if cluster.len() >= params.min_cluster_size {
+                clusters.push(cluster.into_iter().map(NodeIndex::new).collect());
+            }
+        }
+
+        Ok(clusters)
+    }
+}
+
+/// Random candidate if `rng` is set, otherwise the candidate of highest degree, the smallest index on ties
+fn _select_seed(candidates: &BTreeSet<usize>, network: &Network, rng: &mut Option<StdRng>) -> Option<usize> {
+    match rng {
+        Some(rng) if !candidates.is_empty() => candidates.iter().nth(rng.gen_range(0..candidates.len())).copied(),
+        Some(_) => None,
+        None => candidates.iter()
+            .copied()
+            .max_by(|&a, &b| network.adj[a].len().cmp(&network.adj[b].len()).then(b.cmp(&a))),
+    }
+}
+
+/// Binary entropy of the inner link probability `p`
+fn _entropy(p: f64) -> f64 {
+    if p <= 0. || p >= 1. {
+        return 0.;
+    }
+    -p * p.log2() - (1. - p) * (1. - p).log2()
+}
+
+/// Cluster of minimal graph entropy grown from `seed`, as a sorted vector of nodes
+fn _grow_cluster(seed: usize, network: &Network) -> Vec<usize> {
+    let mut cluster = _Cluster::new(network);
+    cluster.insert(seed);
+
+    let mut neighbors: Vec<usize> = network.adj[seed].iter().map(|&(n, _)| n).collect();
+    neighbors.sort_unstable();
+    neighbors.dedup();
+    neighbors.iter().for_each(|&n| cluster.insert(n));
+
+    // remove the neighbors that lower the entropy
+    for n in neighbors {
+        if cluster.change(n) < 0. {
+            cluster.remove(n);
+        }
+    }
+
+    // add the boundary nodes that lower the entropy until none does
+    loop {
+        let boundary: BTreeSet<usize> = cluster.members.iter()
+            .flat_map(|&v| network.adj[v].iter().map(|&(n, _)| n))
+            .filter(|n| !cluster.members.contains(n))
+            .collect();
+
+        let mut added = false;
+        for n in boundary {
+            if cluster.change(n) < 0. {
+                cluster.insert(n);
+                added = true;
+            }
+        }
+
+        if !added {
+            break;
+        }
+    }
+
+    cluster.members.into_iter().collect()
+}
+
+/// Cluster with the weight of the links of every node into it
+struct _Cluster<'a> {
+    network: &'a Network,
+    members: BTreeSet<usize>,
+    /// Weight of the links of every node into the cluster
+    inner: Vec<f64>,
+    /// Weight of all the links of every node, self loops excluded
+    total: Vec<f64>,
+}
+
+impl<'a> _Cluster<'a> {
+    fn new(network: &'a Network) -> Self {
+        let total = network.adj.iter().map(|a| a.iter().map(|&(_, w)| w).sum()).collect();
+        Self { network, members: BTreeSet::new(), inner: vec![0.; network.node_count()], total }
+    }
+
+    fn insert(&mut self, node: usize) {
+        if self.members.insert(node) {
+            self.network.adj[node].iter().for_each(|&(n, w)| self.inner[n] += w);
+        }
+    }
+
+    fn remove(&mut self, node: usize) {
+        if self.members.remove(&node) {
+            self.network.adj[node].iter().for_each(|&(n, w)| self.inner[n] -= w);
+        }
+    }
+
+    /// Change of the graph entropy if `node` joins or leaves the cluster
+    ///
+    /// Only the inner link probabilities of the neighbors of `node` change.
+    fn change(&self, node: usize) -> f64 {
+        let sign = if self.members.contains(&node) { -1. } else { 1. };
+
+        // parallel edges are merged into one run per neighbor
+        let mut links = self.network.adj[node].clone();
+        links.sort_unstable_by_key(|&(n, _)| n);
+        let mut delta: Vec<(usize, f64)> = Vec::with_capacity(links.len());
+        for (n, w) in links {
+            match delta.last_mut() {
+                Some((m, d)) if *m == n => *d += w,
+                _ => delta.push((n, w)),
+            }
+        }
+
+        delta.into_iter()
+            .filter(|&(n, _)| self.total[n] > 0.)
+            .map(|(n, d)| {
+                let before = self.inner[n] / self.total[n];
+                let after = (self.inner[n] + sign * d) / self.total[n];
+                _entropy(after) - _entropy(before)
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph_utils::{bridged_cliques, node_set};
+    use petgraph::graph::UnGraph;
+
+    /// The bridged cliques of graph_utils, the triangle 9, 10, 11 and the isolated node 12
+    fn graph1() -> UnGraph<f64, f64, usize> {
+        let mut graph = bridged_cliques();
+        let nodes: Vec<NodeIndex<usize>> = (0..4).map(|_| graph.add_node(0.)).collect();
+        for &(a, b) in [(0, 1), (0, 2), (1, 2)].iter() {
+            graph.add_edge(nodes[a], nodes[b], 1.);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_entropy() {
+        assert_eq!(_entropy(0.), 0.);
+        assert_eq!(_entropy(1.), 0.);
+        assert_eq!(_entropy(0.5), 1.);
+        approx::assert_abs_diff_eq!(_entropy(0.25), _entropy(0.75));
+    }
+
+    #[test]
+    fn test_graph_entropy() {
+        let clusters = graph1().graph_entropy(&GraphEntropyParams::default()).unwrap();
+        // the leaf 8 joins the clique it hangs from
+        assert_eq!(clusters, vec![node_set(&[0, 1, 2, 3, 4, 8]), node_set(&[5, 6, 7]), node_set(&[9, 10, 11])]);
+
+        let params = GraphEntropyParams::builder().min_cluster_size(1).build().unwrap();
+        let clusters = graph1().graph_entropy(&params).unwrap();
+        assert_eq!(clusters.last(), Some(&node_set(&[12])));
+    }
+
+    #[test]
+    fn test_random_seeds() {
+        let graph = graph1();
+        for seed in 0..5 {
+            let params = GraphEntropyParams::builder().seed(Some(seed)).build().unwrap();
+            let mut clusters: Vec<Vec<usize>> = graph.graph_entropy(&params).unwrap()
+                .into_iter()
+                .map(|c| {
+                    let mut c: Vec<usize> = c.into_iter().map(|n| n.index()).collect();
+                    c.sort_unstable();
+                    c
+                })
+                .collect();
+            clusters.sort();
+            assert_eq!(clusters, vec![vec![0, 1, 2, 3, 4, 8], vec![5, 6, 7], vec![9, 10, 11]]);
+        }
+    }
+
+    #[test]
+    fn test_weighted() {
+        // two 4-cliques, and the node 8 adjacent to 0 and 1 with weight 1 and to 4 and 5 with weight 3
+        let graph = UnGraph::<(), f64>::from_edges([
+            (0, 1, 1.), (0, 2, 1.), (0, 3, 1.), (1, 2, 1.), (1, 3, 1.), (2, 3, 1.),
+            (4, 5, 1.), (4, 6, 1.), (4, 7, 1.), (5, 6, 1.), (5, 7, 1.), (6, 7, 1.),
+            (8, 0, 1.), (8, 1, 1.), (8, 4, 3.), (8, 5, 3.),
+        ]);
+
+        // counted once, the edges of 8 tie it to both cliques
+        let clusters = graph.graph_entropy(&GraphEntropyParams::default()).unwrap();
+        assert_eq!(clusters, vec![node_set(&[0, 1, 2, 3, 8]), node_set(&[4, 5, 6, 7, 8])]);
+
+        let clusters = graph.graph_entropy_weighted(&GraphEntropyParams::default(), |&w| w).unwrap();
+        assert_eq!(clusters, vec![node_set(&[0, 1, 2, 3]), node_set(&[4, 5, 6, 7, 8])]);
+
+        assert!(graph.graph_entropy_weighted(&GraphEntropyParams::default(), |_| -1.).is_err());
+    }
+}
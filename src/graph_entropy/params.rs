@@ -0,0 +1,82 @@
+/*!
+Parameters of [`GraphEntropyExt::graph_entropy`](super::GraphEntropyExt::graph_entropy).
+
+```rust
+use graph_clustering_rs::graph_entropy::*;
+
+let params = GraphEntropyParams::builder()
+    .seed(Some(42))
+    .min_cluster_size(3)
+    .build()
+    .unwrap();
+assert_eq!(params.seed, Some(42));
+
+assert!(GraphEntropyParams::builder().min_cluster_size(0).build().is_err());
+```
+*/
+
+use anyhow::{ensure, Result};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Parameters of graph entropy clustering
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GraphEntropyParams {
+    /// Seed of the random choice of seed vertices, the vertex of highest degree is chosen if `None`
+    pub seed: Option<u64>,
+    /// Smallest number of nodes of a reported cluster
+    pub min_cluster_size: usize,
+}
+
+impl Default for GraphEntropyParams {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            min_cluster_size: 2,
+        }
+    }
+}
+
+impl GraphEntropyParams {
+    /// Start building parameters from the defaults
+    pub fn builder() -> GraphEntropyParamsBuilder {
+        GraphEntropyParamsBuilder {
+            params: Self::default(),
+        }
+    }
+
+    /// Check that the parameters can be used to run graph entropy clustering
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.min_cluster_size > 0, "min_cluster_size must be at least 1");
+        Ok(())
+    }
+}
+
+/// Builder of [`GraphEntropyParams`]
+#[derive(Debug, Clone)]
+pub struct GraphEntropyParamsBuilder {
+    params: GraphEntropyParams,
+}
+
+impl GraphEntropyParamsBuilder {
+    /// Set the seed of the random choice of seed vertices
+    pub fn seed(mut self, seed: Option<u64>) -> Self {
+        self.params.seed = seed;
+        self
+    }
+
+    /// Set the smallest number of nodes of a reported cluster
+    pub fn min_cluster_size(mut self, min_cluster_size: usize) -> Self {
+        self.params.min_cluster_size = min_cluster_size;
+        self
+    }
+
+    /// Validate and return the parameters
+    pub fn build(self) -> Result<GraphEntropyParams> {
+        self.params.validate()?;
+        Ok(self.params)
+    }
+}
@@ -0,0 +1,161 @@
+/*!
+Helpers on weighted petgraph graphs shared by the density based algorithms, MCODE, DPClus, IPCA and CoAch.
+
+Edge directions are ignored, so that an undirected edge and a directed edge between the same nodes count alike.
+*/
+
+use std::{collections::HashSet, iter::Sum};
+
+use num_traits::{Float, zero};
+use petgraph::{EdgeType, Incoming, graph::{Graph, IndexType, NodeIndex}, visit::EdgeRef};
+
+/// Distinct neighbors of `node` other than itself
+pub(crate) fn neighbors<N, E, Ty, Ix>(node: NodeIndex<Ix>, graph: &Graph<N, E, Ty, Ix>) -> HashSet<NodeIndex<Ix>>
+where
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    graph.neighbors_undirected(node).filter(|&n| n != node).collect()
+}
+
+/// Sum of the weights of the edges of `node`, incoming and outgoing
+pub(crate) fn sum_edge_weights<W, Ty, Ix>(node: NodeIndex<Ix>, graph: &Graph<W, W, Ty, Ix>) -> W
+where
+    W: Float + Sum,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    let outgoing = graph.edges(node).map(|e| *e.weight()).sum::<W>();
+    if !graph.is_directed() {
+        return outgoing;
+    }
+    // self loops are counted once, with the outgoing edges
+    outgoing + graph.edges_directed(node, Incoming).filter(|e| e.source() != node).map(|e| *e.weight()).sum::<W>()
+}
+
+/// Sum of the edge weights between distinct nodes divided by the number of node pairs
+pub(crate) fn density<W, Ty, Ix>(nodes: &HashSet<NodeIndex<Ix>>, graph: &Graph<W, W, Ty, Ix>) -> W
+where
+    W: Float + Sum,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    let n = nodes.len();
+    if n < 2 {
+        return zero();
+    }
+
+    let directed = graph.is_directed();
+    let weight: W = nodes.iter()
+        .flat_map(|&a| graph.edges(a).filter_map(move |e| {
+            let b = if e.source() == a { e.target() } else { e.source() };
+            // each undirected edge is seen from both of its ends
+            if b != a && nodes.contains(&b) && (directed || a.index() < b.index()) {
+                Some(*e.weight())
+            } else {
+                None
+            }
+        }))
+        .sum();
+
+    weight / W::from(n * (n - 1) / 2).unwrap()
+}
+
+/// Copy of the graph whose edge weights are the edge priorities and whose node weights are the node priorities
+///
+/// The priority of an edge is the number of common neighbors of its ends, or its weight if `weighted`,
+/// and the priority of a node is the sum of the priorities of its edges.
+pub(crate) fn priority_graph<W, Ty, Ix>(graph: &Graph<W, W, Ty, Ix>, weighted: bool) -> Graph<W, W, Ty, Ix>
+where
+    W: Float + Sum,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    let mut priority = graph.map(
+        |_, _| zero(),
+        |e, &w| {
+            if weighted {
+                return w;
+            }
+            let (a, b) = graph.edge_endpoints(e).unwrap();
+            let na = neighbors(a, graph);
+            W::from(neighbors(b, graph).intersection(&na).count()).unwrap()
+        },
+    );
+
+    for node in graph.node_indices() {
+        priority[node] = sum_edge_weights(node, &priority);
+    }
+
+    priority
+}
+
+/// Nodes of the given indices, to compare clusters in tests
+#[cfg(test)]
+pub(crate) fn node_set<Ix: IndexType>(nodes: &[usize]) -> HashSet<NodeIndex<Ix>> {
+    nodes.iter().map(|&i| NodeIndex::new(i)).collect()
+}
+
+/// K5 on 0..=4, a bridge 4-5, K3 on 5..=7 and the leaf 8 attached to 0
+#[cfg(test)]
+pub(crate) fn bridged_cliques() -> Graph<f64, f64, petgraph::Undirected, usize> {
+    Graph::<f64, f64, petgraph::Undirected, usize>::from_edges([
+        (0, 1, 1.), (0, 2, 1.), (0, 3, 1.), (0, 4, 1.),
+        (1, 2, 1.), (1, 3, 1.), (1, 4, 1.),
+        (2, 3, 1.), (2, 4, 1.),
+        (3, 4, 1.),
+        (4, 5, 1.),
+        (5, 6, 1.), (5, 7, 1.), (6, 7, 1.),
+        (0, 8, 1.),
+    ])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use petgraph::graph::{DiGraph, UnGraph};
+
+    /// K4 on 0..=3 and the leaf 4 attached to 3
+    fn graph1() -> UnGraph<f64, f64> {
+        UnGraph::from_edges([(0, 1, 1.), (0, 2, 1.), (0, 3, 1.), (1, 2, 1.), (1, 3, 1.), (2, 3, 1.), (3, 4, 2.)])
+    }
+
+    #[test]
+    fn test_density() {
+        let graph = graph1();
+        assert_eq!(density(&node_set(&[0, 1, 2, 3]), &graph), 1.);
+        assert_eq!(density(&node_set(&[2, 3, 4]), &graph), 1.);
+        assert_eq!(density(&node_set(&[4]), &graph), 0.);
+        assert_eq!(sum_edge_weights(NodeIndex::new(3), &graph), 5.);
+    }
+
+    #[test]
+    fn test_priority_graph() {
+        let graph = graph1();
+        let priority = priority_graph(&graph, false);
+        // 2 common neighbors on each of the 3 edges of 0 inside the clique
+        assert_eq!(priority[NodeIndex::new(0)], 6.);
+        assert_eq!(priority[NodeIndex::new(4)], 0.);
+
+        let weighted = priority_graph(&graph, true);
+        assert_eq!(weighted[NodeIndex::new(3)], 5.);
+        assert_eq!(neighbors(NodeIndex::new(4), &graph), node_set(&[3]));
+    }
+
+    #[test]
+    fn test_directed() {
+        // the same edges pointing one way
+        let mut directed = DiGraph::<f64, f64>::from_edges([(0, 1, 1.), (0, 2, 1.), (0, 3, 1.), (1, 2, 1.), (1, 3, 1.), (2, 3, 1.), (3, 4, 2.)]);
+        assert_eq!(sum_edge_weights(NodeIndex::new(3), &directed), 5.);
+        assert_eq!(sum_edge_weights(NodeIndex::new(4), &directed), 2.);
+        assert_eq!(density(&node_set(&[0, 1, 2, 3]), &directed), 1.);
+
+        let priority = priority_graph(&directed, false);
+        let undirected = priority_graph(&graph1(), false);
+        assert!(directed.node_indices().all(|n| priority[n] == undirected[n]));
+
+        // a self loop is counted once
+        directed.add_edge(NodeIndex::new(4), NodeIndex::new(4), 1.);
+        assert_eq!(sum_edge_weights(NodeIndex::new(4), &directed), 3.);
+    }
+}
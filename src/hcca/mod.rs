@@ -0,0 +1,294 @@
+/*!
+HCCA (Heuristic Cluster Chiseling Algorithm) partitions a network into non-overlapping clusters. It was developed by Mutwil et al. for co-expression networks,
+details of the algorithm can be found in [Assembly of an interactive correlation network for the Arabidopsis genome using a novel heuristic clustering algorithm](https://doi.org/10.1104/pp.109.145318).
+
+In every round, the node vicinity network of each remaining node, the nodes within `step_size` steps, is chiseled
+by removing the nodes with more connections outside than inside the vicinity until none is left.
+The chiseled vicinities with an accepted size become candidates, and non-overlapping candidates are taken as clusters in order of
+decreasing connectivity, the fraction of their edges that stay inside the cluster. Rounds are repeated on the remaining nodes
+until no cluster is found, and the nodes left over are returned as unassigned.
+
+Edges are unweighted and their directions are ignored.
+
+# Example Usage
+
+```rust
+use graph_clustering_rs::hcca::*;
+use petgraph::graph::{NodeIndex, UnGraph};
+
+// two 4-cliques joined by the edge 3-4, and the leaf 8
+let graph = UnGraph::<(), ()>::from_edges(&[
+    (0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3),
+    (3, 4),
+    (4, 5), (4, 6), (4, 7), (5, 6), (5, 7), (6, 7),
+    (7, 8),
+]);
+
+let params = HccaParams::builder().step_size(1).min_cluster_size(3).max_cluster_size(4).build().unwrap();
+let res = graph.hcca(&params).unwrap();
+
+let nodes = |v: &[usize]| v.iter().map(|&i| NodeIndex::new(i)).collect::<Vec<_>>();
+assert_eq!(res.clusters, vec![nodes(&[0, 1, 2, 3]), nodes(&[4, 5, 6, 7])]);
+assert_eq!(res.unassigned, nodes(&[8]));
+```
+*/
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use anyhow::Result;
+use petgraph::{EdgeType, graph::{Graph, IndexType, NodeIndex, UnGraph}, visit::{Bfs, EdgeRef, NodeFiltered}};
+
+use crate::clustering::Clustering;
+
+pub mod params;
+
+pub use params::{HccaParams, HccaParamsBuilder};
+
+/// Result of HCCA
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HccaResult<Ix> {
+    /// Clusters with sorted nodes, in the order they were accepted
+    pub clusters: Vec<Vec<NodeIndex<Ix>>>,
+    /// Sorted nodes that do not belong to any cluster
+    pub unassigned: Vec<NodeIndex<Ix>>,
+}
+
+impl<Ix: IndexType> HccaResult<Ix> {
+    /// Clusters as a [`Clustering`], in which the unassigned nodes belong to no cluster
+    pub fn clustering(&self) -> Clustering {
+        let node_count = self.clusters.iter().map(|c| c.len()).sum::<usize>() + self.unassigned.len();
+        let clusters = self.clusters.iter()
+            .map(|c| c.iter().map(|n| n.index()).collect())
+            .collect();
+        Clustering::_new(node_count, clusters)
+    }
+}
+
+pub trait HccaExt<Ix> {
+    /// Partition the graph with HCCA
+    fn hcca(&self, params: &HccaParams) -> Result<HccaResult<Ix>>;
+}
+
+impl<N, E, Ty, Ix> HccaExt<Ix> for Graph<N, E, Ty, Ix>
+where
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    fn hcca(&self, params: &HccaParams) -> Result<HccaResult<Ix>> {
+        params.validate()?;
+
+        let graph = _simple(self);
+        let mut remaining: HashSet<NodeIndex<Ix>> = self.node_indices().collect();
+        let mut clusters = Vec::new();
+
+        loop {
+            let mut candidates: Vec<(f64, Vec<NodeIndex<Ix>>)> = Vec::new();
+
+            for seed in self.node_indices().filter(|n| remaining.contains(n)) {
+                let vicinity = _vicinity(seed, params.step_size, &remaining, &graph);
+                let core = _chisel(seed, vicinity, &remaining, &graph);
+
+                if core.len() >= params.min_cluster_size && core.len() <= params.max_cluster_size {
+                    let score = _connectivity(&core, &remaining, &graph);
+                    let mut core: Vec<NodeIndex<Ix>> = core.into_iter().collect();
+                    core.sort();
+                    candidates.push((score, core));
+                }
+            }
+
+            if candidates.is_empty() {
+                break;
+            }
+
+            // best connectivity first, then larger clusters, then smaller node indices
+            candidates.sort_by(|a, b| {
+                b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal)
+                    .then(b.1.len().cmp(&a.1.len()))
+                    .then(a.1.cmp(&b.1))
+            });
+
+            for (_, cluster) in candidates {
+                if cluster.iter().all(|n| remaining.contains(n)) {
+                    cluster.iter().for_each(|n| { remaining.remove(n); });
+                    clusters.push(cluster);
+                }
+            }
+        }
+
+        let mut unassigned: Vec<NodeIndex<Ix>> = remaining.into_iter().collect();
+        unassigned.sort();
+
+        Ok(HccaResult { clusters, unassigned })
+    }
+}
+
+/// Undirected copy of the graph without self loops and parallel edges
+fn _simple<N, E, Ty, Ix>(graph: &Graph<N, E, Ty, Ix>) -> UnGraph<(), (), Ix>
+where
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    let mut simple = UnGraph::with_capacity(graph.node_count(), graph.edge_count());
+    graph.node_indices().for_each(|_| { simple.add_node(()); });
+    for edge in graph.edge_references().filter(|e| e.source() != e.target()) {
+        simple.update_edge(edge.source(), edge.target(), ());
+    }
+    simple
+}
+
+/// Remaining nodes within `step_size` steps from `seed` through remaining nodes
+fn _vicinity<Ix: IndexType>(seed: NodeIndex<Ix>, step_size: usize, remaining: &HashSet<NodeIndex<Ix>>, graph: &UnGraph<(), (), Ix>) -> HashSet<NodeIndex<Ix>> {
+    let mut vicinity: HashSet<NodeIndex<Ix>> = std::iter::once(seed).collect();
+    let mut frontier = vec![seed];
+
+    for _ in 0..step_size {
+        let mut next = Vec::new();
+        for &node in frontier.iter() {
+            for n in graph.neighbors(node) {
+                if remaining.contains(&n) && vicinity.insert(n) {
+                    next.push(n);
+                }
+            }
+        }
+        frontier = next;
+    }
+
+    vicinity
+}
+
+/// Remove the nodes with more remaining neighbors outside than inside until none is left,
+/// and keep the connected part containing the seed, empty if the seed was removed
+fn _chisel<Ix: IndexType>(seed: NodeIndex<Ix>, mut vicinity: HashSet<NodeIndex<Ix>>, remaining: &HashSet<NodeIndex<Ix>>, graph: &UnGraph<(), (), Ix>) -> HashSet<NodeIndex<Ix>> {
+    loop {
+        let removed: Vec<NodeIndex<Ix>> = vicinity.iter()
+            .copied()
+            .filter(|&node| {
+                let (mut inside, mut outside) = (0, 0);
+                for n in graph.neighbors(node) {
+                    if vicinity.contains(&n) {
+                        inside += 1;
+                    } else if remaining.contains(&n) {
+                        outside += 1;
+                    }
+                }
+                outside > inside
+            })
+            .collect();
+
+        if removed.is_empty() {
+            break;
+        }
+        removed.iter().for_each(|n| { vicinity.remove(n); });
+    }
+
+    if !vicinity.contains(&seed) {
+        return HashSet::new();
+    }
+
+    let chiseled = NodeFiltered(graph, &vicinity);
+    let mut bfs = Bfs::new(&chiseled, seed);
+    let mut component = HashSet::new();
+    while let Some(node) = bfs.next(&chiseled) {
+        component.insert(node);
+    }
+
+    component
+}
+
+/// Fraction of the edges of the cluster to remaining nodes that stay inside the cluster
+fn _connectivity<Ix: IndexType>(cluster: &HashSet<NodeIndex<Ix>>, remaining: &HashSet<NodeIndex<Ix>>, graph: &UnGraph<(), (), Ix>) -> f64 {
+    let (mut inside, mut boundary) = (0, 0);
+    for &node in cluster.iter() {
+        for n in graph.neighbors(node) {
+            if cluster.contains(&n) {
+                inside += 1;
+            } else if remaining.contains(&n) {
+                boundary += 1;
+            }
+        }
+    }
+
+    // edges inside the cluster were counted from both ends
+    let inside = inside / 2;
+    if inside + boundary == 0 {
+        return 0.;
+    }
+    inside as f64 / (inside + boundary) as f64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use petgraph::graph::UnGraph;
+
+    /// K5 on 0..=4 and K5 on 5..=9 joined by the edge 4-5, and the path 9-10-11
+    fn graph1() -> UnGraph<(), ()> {
+        let mut edges = Vec::new();
+        for base in [0u32, 5] {
+            for a in 0..5 {
+                for b in (a + 1)..5 {
+                    edges.push((base + a, base + b));
+                }
+            }
+        }
+        edges.extend([(4, 5), (9, 10), (10, 11)]);
+        UnGraph::from_edges(edges)
+    }
+
+    fn nodes(v: &[usize]) -> Vec<NodeIndex> {
+        v.iter().map(|&i| NodeIndex::new(i)).collect()
+    }
+
+    #[test]
+    fn test_step_size() {
+        let graph = graph1();
+
+        let params = HccaParams::builder().step_size(1).min_cluster_size(3).max_cluster_size(5).build().unwrap();
+        let res = graph.hcca(&params).unwrap();
+        assert_eq!(res.clusters, vec![nodes(&[0, 1, 2, 3, 4]), nodes(&[5, 6, 7, 8, 9])]);
+        assert_eq!(res.unassigned, nodes(&[10, 11]));
+        assert_eq!(res.clustering().unassigned(), vec![10, 11]);
+
+        // a larger vicinity lets the path join the second clique
+        let params = HccaParams::builder().step_size(2).min_cluster_size(3).max_cluster_size(8).build().unwrap();
+        let res = graph.hcca(&params).unwrap();
+        assert_eq!(res.clusters, vec![nodes(&[5, 6, 7, 8, 9, 10, 11]), nodes(&[0, 1, 2, 3, 4])]);
+        assert!(res.unassigned.is_empty());
+    }
+
+    #[test]
+    fn test_chisel() {
+        let graph = graph1();
+        let remaining: HashSet<NodeIndex> = graph.node_indices().collect();
+        let vicinity = _vicinity(NodeIndex::new(0), 1, &remaining, &graph);
+        assert_eq!(vicinity.len(), 5);
+
+        let vicinity = _vicinity(NodeIndex::new(4), 1, &remaining, &graph);
+        let core = _chisel(NodeIndex::new(4), vicinity, &remaining, &graph);
+        assert_eq!(core, nodes(&[0, 1, 2, 3, 4]).into_iter().collect());
+    }
+
+    #[test]
+    fn test_directed_and_parallel_edges() {
+        let graph = graph1();
+        let params = HccaParams::builder().step_size(1).min_cluster_size(3).max_cluster_size(5).build().unwrap();
+
+        // reversed directions, doubled edges and self loops do not change the clusters
+        let mut edges: Vec<(u32, u32)> = graph.raw_edges().iter().map(|e| (e.target().index() as u32, e.source().index() as u32)).collect();
+        edges.extend(graph.raw_edges().iter().map(|e| (e.source().index() as u32, e.target().index() as u32)));
+        edges.extend([(0, 0), (11, 11)]);
+        let directed = petgraph::graph::DiGraph::<(), ()>::from_edges(edges);
+
+        assert_eq!(directed.hcca(&params).unwrap(), graph.hcca(&params).unwrap());
+    }
+
+    #[test]
+    fn test_no_cluster() {
+        let graph = graph1();
+        let params = HccaParams::builder().min_cluster_size(20).max_cluster_size(30).build().unwrap();
+        let res = graph.hcca(&params).unwrap();
+        assert!(res.clusters.is_empty());
+        assert_eq!(res.unassigned.len(), 12);
+    }
+}
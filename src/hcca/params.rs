@@ -0,0 +1,99 @@
+/*!
+Parameters of [`HccaExt::hcca`](super::HccaExt::hcca).
+
+The defaults follow the original publication: a step size of 3 and clusters of 40 to 200 nodes.
+
+```rust
+use graph_clustering_rs::hcca::*;
+
+let params = HccaParams::builder()
+    .step_size(2)
+    .min_cluster_size(5)
+    .max_cluster_size(50)
+    .build()
+    .unwrap();
+assert_eq!(params.step_size, 2);
+
+assert!(HccaParams::builder().min_cluster_size(300).build().is_err());
+```
+*/
+
+use anyhow::{ensure, Result};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Parameters of HCCA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct HccaParams {
+    /// Depth of the node vicinity network around every seed
+    pub step_size: usize,
+    /// Smallest number of nodes of a cluster
+    pub min_cluster_size: usize,
+    /// Largest number of nodes of a cluster
+    pub max_cluster_size: usize,
+}
+
+impl Default for HccaParams {
+    fn default() -> Self {
+        Self {
+            step_size: 3,
+            min_cluster_size: 40,
+            max_cluster_size: 200,
+        }
+    }
+}
+
+impl HccaParams {
+    /// Start building parameters from the defaults
+    pub fn builder() -> HccaParamsBuilder {
+        HccaParamsBuilder {
+            params: Self::default(),
+        }
+    }
+
+    /// Check that the parameters can be used to run HCCA
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.step_size > 0, "step_size must be at least 1");
+        ensure!(self.min_cluster_size > 0, "min_cluster_size must be at least 1");
+        ensure!(
+            self.min_cluster_size <= self.max_cluster_size,
+            "min_cluster_size ({}) must not be larger than max_cluster_size ({})", self.min_cluster_size, self.max_cluster_size
+        );
+        Ok(())
+    }
+}
+
+/// Builder of [`HccaParams`]
+#[derive(Debug, Clone)]
+pub struct HccaParamsBuilder {
+    params: HccaParams,
+}
+
+impl HccaParamsBuilder {
+    /// Set the depth of the node vicinity networks
+    pub fn step_size(mut self, step_size: usize) -> Self {
+        self.params.step_size = step_size;
+        self
+    }
+
+    /// Set the smallest number of nodes of a cluster
+    pub fn min_cluster_size(mut self, min_cluster_size: usize) -> Self {
+        self.params.min_cluster_size = min_cluster_size;
+        self
+    }
+
+    /// Set the largest number of nodes of a cluster
+    pub fn max_cluster_size(mut self, max_cluster_size: usize) -> Self {
+        self.params.max_cluster_size = max_cluster_size;
+        self
+    }
+
+    /// Validate and return the parameters
+    pub fn build(self) -> Result<HccaParams> {
+        self.params.validate()?;
+        Ok(self.params)
+    }
+}
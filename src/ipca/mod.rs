@@ -0,0 +1,184 @@
+/*!
+IPCA finds overlapping protein complexes of small diameter. It was developed by Li et al. as a modification of DPClus,
+details of the algorithm can be found in [Modifying the DPClus algorithm for identifying protein complexes based on new topological structures](https://doi.org/10.1186/1471-2105-9-398).
+
+As in [DPClus](crate::dpclus), the weight of an edge is the number of common neighbors of its ends and the weight of a node is the sum of the weights of its edges.
+Nodes that are not yet in a cluster seed new clusters in order of decreasing weight. A cluster takes its neighbors in order of
+decreasing number of links to the cluster, then decreasing weight, as long as one of them satisfies
+
+- the interaction probability, the number of links to the cluster divided by the size of the cluster, is at least `t_in`, and
+- the shortest path between any two nodes of the grown cluster is at most `diameter` steps long.
+
+Clusters may overlap, and edge weights are not used.
+
+# Example Usage
+
+```rust
+use graph_clustering_rs::ipca::*;
+use petgraph::graph::{NodeIndex, UnGraph};
+
+// two 4-cliques sharing the node 3
+let graph = UnGraph::<f64, f64>::from_edges(&[
+    (0, 1, 1.), (0, 2, 1.), (0, 3, 1.), (1, 2, 1.), (1, 3, 1.), (2, 3, 1.),
+    (3, 4, 1.), (3, 5, 1.), (3, 6, 1.), (4, 5, 1.), (4, 6, 1.), (5, 6, 1.),
+]);
+
+let complexes = graph.ipca(&IpcaParams::default()).unwrap();
+assert_eq!(complexes.len(), 2);
+assert!(complexes.iter().all(|c| c.len() == 4 && c.contains(&NodeIndex::new(3))));
+```
+*/
+
+use std::{cmp::Ordering, collections::HashSet, iter::Sum};
+
+use anyhow::Result;
+use num_traits::Float;
+use petgraph::{EdgeType, graph::{Graph, IndexType, NodeIndex}};
+
+use crate::graph_utils::{neighbors, priority_graph};
+
+pub mod params;
+
+pub use params::{IpcaParams, IpcaParamsBuilder};
+
+pub trait IpcaExt<W, Ix> {
+    /// Find overlapping complexes with IPCA, in the order they were found
+    ///
+    /// Complexes of a single node are not reported.
+    fn ipca(&self, params: &IpcaParams<W>) -> Result<Vec<HashSet<NodeIndex<Ix>>>>;
+}
+
+impl<W, Ty, Ix> IpcaExt<W, Ix> for Graph<W, W, Ty, Ix>
+where
+    W: Float + Sum,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    fn ipca(&self, params: &IpcaParams<W>) -> Result<Vec<HashSet<NodeIndex<Ix>>>> {
+        params.validate()?;
+
+        let weights = priority_graph(self, false);
+
+        // seeds in order of decreasing weight, ties in order of index
+        let mut seeds: Vec<NodeIndex<Ix>> = self.node_indices().collect();
+        seeds.sort_by(|&a, &b| weights[b].partial_cmp(&weights[a]).unwrap_or(Ordering::Equal).then(a.cmp(&b)));
+
+        let mut clustered: HashSet<NodeIndex<Ix>> = HashSet::new();
+        let mut complexes = Vec::new();
+
+        for seed in seeds {
+            if clustered.contains(&seed) { continue; }
+
+            let complex = _extend_cluster(seed, &weights, params);
+            if complex.len() > 1 {
+                clustered.extend(complex.iter().copied());
+                complexes.push(complex);
+            }
+        }
+
+        Ok(complexes)
+    }
+}
+
+/// Add the neighbor of highest priority that satisfies the interaction probability and the diameter until none does
+fn _extend_cluster<W, Ty, Ix>(seed: NodeIndex<Ix>, weights: &Graph<W, W, Ty, Ix>, params: &IpcaParams<W>) -> HashSet<NodeIndex<Ix>>
+where
+    W: Float + Sum,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    let mut cluster: HashSet<NodeIndex<Ix>> = std::iter::once(seed).collect();
+
+    loop {
+        let boundary: HashSet<NodeIndex<Ix>> = cluster.iter()
+            .flat_map(|&n| neighbors(n, weights))
+            .filter(|n| !cluster.contains(n))
+            .collect();
+
+        // most links to the cluster, then highest weight, then smallest index
+        let mut candidates: Vec<(NodeIndex<Ix>, usize)> = boundary.into_iter()
+            .map(|n| (n, neighbors(n, weights).intersection(&cluster).count()))
+            .collect();
+        candidates.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then(weights[b.0].partial_cmp(&weights[a.0]).unwrap_or(Ordering::Equal))
+                .then(a.0.cmp(&b.0))
+        });
+
+        let size = W::from(cluster.len()).unwrap();
+        let added = candidates.into_iter().find(|&(n, links)| {
+            W::from(links).unwrap() / size >= params.t_in && _eccentricity_within(n, &cluster, params.diameter, weights)
+        });
+
+        match added {
+            Some((n, _)) => { cluster.insert(n); },
+            None => break,
+        }
+    }
+
+    cluster
+}
+
+/// Whether every node of the cluster is at most `diameter` steps from `node` through nodes of the cluster
+///
+/// The cluster already has a diameter of at most `diameter`, and adding a node does not lengthen the other shortest paths.
+fn _eccentricity_within<W, Ty, Ix>(node: NodeIndex<Ix>, cluster: &HashSet<NodeIndex<Ix>>, diameter: usize, graph: &Graph<W, W, Ty, Ix>) -> bool
+where
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    let mut reached: HashSet<NodeIndex<Ix>> = std::iter::once(node).collect();
+    let mut frontier = vec![node];
+
+    for _ in 0..diameter {
+        let mut next = Vec::new();
+        for &v in frontier.iter() {
+            for n in graph.neighbors_undirected(v) {
+                if cluster.contains(&n) && reached.insert(n) {
+                    next.push(n);
+                }
+            }
+        }
+        frontier = next;
+    }
+
+    cluster.iter().all(|n| reached.contains(n))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph_utils::{bridged_cliques, node_set};
+
+    #[test]
+    fn test_ipca() {
+        let complexes = bridged_cliques().ipca(&IpcaParams::default()).unwrap();
+        // 5 and 8 pull in the clique, which is within 2 steps and linked to half of their two-node clusters
+        assert_eq!(complexes, vec![
+            node_set(&[0, 1, 2, 3, 4]),
+            node_set(&[0, 1, 2, 3, 4, 5]),
+            node_set(&[5, 6, 7]),
+            node_set(&[0, 1, 2, 3, 4, 8]),
+        ]);
+    }
+
+    #[test]
+    fn test_diameter() {
+        // with diameter 1 only cliques are found
+        let params = IpcaParams::builder().diameter(1).build().unwrap();
+        let complexes = bridged_cliques().ipca(&params).unwrap();
+        assert_eq!(complexes, vec![
+            node_set(&[0, 1, 2, 3, 4]),
+            node_set(&[4, 5]),
+            node_set(&[5, 6, 7]),
+            node_set(&[0, 8]),
+        ]);
+    }
+
+    #[test]
+    fn test_t_in() {
+        let params = IpcaParams::builder().t_in(0.6).build().unwrap();
+        let complexes = bridged_cliques().ipca(&params).unwrap();
+        assert_eq!(complexes[1], node_set(&[4, 5]));
+    }
+}
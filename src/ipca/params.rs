@@ -0,0 +1,86 @@
+/*!
+Parameters of [`IpcaExt::ipca`](super::IpcaExt::ipca).
+
+The defaults are a diameter of 2 and an interaction probability threshold of 0.5.
+
+```rust
+use graph_clustering_rs::ipca::*;
+
+let params: IpcaParams<f64> = IpcaParams::builder()
+    .t_in(0.6)
+    .diameter(3)
+    .build()
+    .unwrap();
+assert_eq!(params.diameter, 3);
+
+assert!(IpcaParams::<f64>::builder().diameter(0).build().is_err());
+```
+*/
+
+use anyhow::{ensure, Result};
+use num_traits::{Float, one, zero};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Parameters of IPCA
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, bound(deserialize = "W: Float + Deserialize<'de>")))]
+pub struct IpcaParams<W> {
+    /// Minimum interaction probability of a node joining a cluster
+    pub t_in: W,
+    /// Maximum shortest path length between two nodes of a cluster
+    pub diameter: usize,
+}
+
+impl<W: Float> Default for IpcaParams<W> {
+    fn default() -> Self {
+        Self {
+            t_in: W::from(0.5).unwrap(),
+            diameter: 2,
+        }
+    }
+}
+
+impl<W: Float> IpcaParams<W> {
+    /// Start building parameters from the defaults
+    pub fn builder() -> IpcaParamsBuilder<W> {
+        IpcaParamsBuilder {
+            params: Self::default(),
+        }
+    }
+
+    /// Check that the parameters can be used to run IPCA
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.t_in > zero() && self.t_in <= one(), "t_in must be in (0, 1]");
+        ensure!(self.diameter > 0, "diameter must be at least 1");
+        Ok(())
+    }
+}
+
+/// Builder of [`IpcaParams`]
+#[derive(Debug, Clone)]
+pub struct IpcaParamsBuilder<W> {
+    params: IpcaParams<W>,
+}
+
+impl<W: Float> IpcaParamsBuilder<W> {
+    /// Set the minimum interaction probability of a node joining a cluster
+    pub fn t_in(mut self, t_in: W) -> Self {
+        self.params.t_in = t_in;
+        self
+    }
+
+    /// Set the maximum shortest path length inside a cluster
+    pub fn diameter(mut self, diameter: usize) -> Self {
+        self.params.diameter = diameter;
+        self
+    }
+
+    /// Validate and return the parameters
+    pub fn build(self) -> Result<IpcaParams<W>> {
+        self.params.validate()?;
+        Ok(self.params)
+    }
+}
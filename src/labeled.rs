@@ -0,0 +1,334 @@
+/*!
+Graphs whose nodes are named by labels, such as gene or protein IDs.
+
+[`LabeledGraph`] reads the "ABC" format of the mcl program: every line holds two labels and an optional weight, separated by tabs.
+Lines without a tab are split on whitespace, so labels may contain spaces only in tab-separated lines.
+Edge lists of integer node IDs and dense adjacency matrices are read as well, with the IDs or row numbers as labels.
+Labels are interned in the order they first appear, so node `i` of the graph is named by `labels.label(i)`.
+Any [`Clustering`] of the graph can then be written back as a cluster file with one cluster per line and tab-separated labels,
+as `mcl --abc` writes it.
+
+Empty lines and lines starting with `#` are skipped, a missing weight is 1, and repeated pairs become parallel edges whose weights add up
+in the algorithms.
+
+```rust
+use graph_clustering_rs::clusterer::{GraphClusterer, Louvain};
+use graph_clustering_rs::labeled::LabeledGraph;
+use graph_clustering_rs::louvain::LouvainParams;
+
+// two triangles joined by the edge BRCA1 TP53
+let abc = "\
+BRCA1\tBARD1\t2.5
+BRCA1\tRAD51\t1
+BARD1\tRAD51
+TP53\tMDM2
+TP53\tMDM4
+MDM2\tMDM4
+BRCA1\tTP53\t0.5
+";
+let labeled = LabeledGraph::from_abc(abc.as_bytes()).unwrap();
+assert_eq!(labeled.labels.index("TP53"), Some(3));
+
+let clustering = Louvain(LouvainParams::default()).cluster(&labeled.graph).unwrap();
+let mut out = Vec::new();
+labeled.write_clusters(&clustering, &mut out).unwrap();
+assert_eq!(String::from_utf8(out).unwrap(), "BRCA1\tBARD1\tRAD51\nTP53\tMDM2\tMDM4\n");
+```
+*/
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use anyhow::{bail, ensure, Context, Result};
+use petgraph::graph::{NodeIndex, UnGraph};
+
+use crate::clustering::Clustering;
+
+/// Largest number of IDs missing from an edge list, which become isolated nodes
+const MAX_MISSING_IDS: usize = 1 << 20;
+
+/// Interned labels, numbered from 0 in order of insertion
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Labels {
+    labels: Vec<String>,
+    indices: HashMap<String, usize>,
+}
+
+impl Labels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index of `label`, added as the next index if it is new
+    pub fn intern(&mut self, label: &str) -> usize {
+        if let Some(&i) = self.indices.get(label) {
+            return i;
+        }
+        let i = self.labels.len();
+        self.labels.push(label.to_string());
+        self.indices.insert(label.to_string(), i);
+        i
+    }
+
+    /// Index of `label`, `None` if it was never interned
+    pub fn index(&self, label: &str) -> Option<usize> {
+        self.indices.get(label).copied()
+    }
+
+    /// Label of the index `i`
+    pub fn label(&self, i: usize) -> Option<&str> {
+        self.labels.get(i).map(|l| l.as_str())
+    }
+
+    /// All labels in order of index
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
+
+/// Undirected graph whose node `i` is named by the `i`-th label
+#[derive(Debug, Clone)]
+pub struct LabeledGraph {
+    pub graph: UnGraph<(), f64>,
+    pub labels: Labels,
+}
+
+impl LabeledGraph {
+    /// Read a graph in the ABC format
+    pub fn from_abc<R: BufRead>(reader: R) -> Result<Self> {
+        let mut graph = UnGraph::<(), f64>::default();
+        let mut labels = Labels::new();
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = if line.contains('\t') {
+                line.split('\t').map(str::trim).collect()
+            } else {
+                line.split_whitespace().collect()
+            };
+            let weight = match fields.len() {
+                2 => 1.,
+                3 => fields[2].parse::<f64>().with_context(|| format!("line {}: invalid weight {}", i + 1, fields[2]))?,
+                n => bail!("line {}: expected two labels and an optional weight, found {} fields", i + 1, n),
+            };
+            ensure!(!fields[0].is_empty() && !fields[1].is_empty(), "line {}: labels must not be empty", i + 1);
+            ensure!(weight.is_finite() && weight >= 0., "line {}: weight must be finite and not negative, found {}", i + 1, weight);
+
+            let a = _node(&mut graph, &mut labels, fields[0]);
+            let b = _node(&mut graph, &mut labels, fields[1]);
+            graph.add_edge(a, b, weight);
+        }
+
+        Ok(Self { graph, labels })
+    }
+
+    /// Read a file in the ABC format
+    pub fn read_abc<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_abc(_open(path.as_ref())?)
+    }
+
+    /// Read an edge list of integer node IDs with optional weights, in the same layout as the ABC format
+    ///
+    /// Nodes are numbered by their IDs and labeled by them, so IDs missing from the list become isolated nodes. IDs that do not
+    /// fit a graph index, or that would leave more than about a million IDs missing, are an error.
+    pub fn from_edge_list<R: BufRead>(reader: R) -> Result<Self> {
+        let abc = Self::from_abc(reader)?;
+
+        let mut ids = Vec::with_capacity(abc.labels.len());
+        for label in abc.labels.labels() {
+            ids.push(label.parse::<usize>().with_context(|| format!("invalid node ID {}", label))?);
+        }
+
+        let node_count = match ids.iter().max() {
+            Some(&m) => {
+                ensure!(m < NodeIndex::<u32>::end().index(), "node ID {} is too large for a graph index", m);
+                ensure!(m + 1 - ids.len() <= MAX_MISSING_IDS, "node ID {} is far larger than the {} distinct IDs", m, ids.len());
+                m + 1
+            }
+            None => 0,
+        };
+        let mut graph = UnGraph::<(), f64>::with_capacity(node_count, abc.graph.edge_count());
+        let mut labels = Labels::new();
+        for i in 0..node_count {
+            graph.add_node(());
+            labels.intern(&i.to_string());
+        }
+        for e in abc.graph.raw_edges() {
+            graph.add_edge(NodeIndex::new(ids[e.source().index()]), NodeIndex::new(ids[e.target().index()]), e.weight);
+        }
+
+        Ok(Self { graph, labels })
+    }
+
+    /// Read a file of an edge list, see [`from_edge_list`](LabeledGraph::from_edge_list)
+    pub fn read_edge_list<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_edge_list(_open(path.as_ref())?)
+    }
+
+    /// Read a symmetric adjacency matrix with one whitespace-separated row per line
+    ///
+    /// Nodes are labeled by their row numbers from 0, and nonzero entries on and above the diagonal become edges.
+    pub fn from_matrix<R: BufRead>(reader: R) -> Result<Self> {
+        let mut rows: Vec<Vec<f64>> = Vec::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let row = line.split_whitespace()
+                .map(|x| x.parse::<f64>().with_context(|| format!("line {}: invalid weight {}", i + 1, x)))
+                .collect::<Result<Vec<f64>>>()?;
+            rows.push(row);
+        }
+
+        let n = rows.len();
+        let mut graph = UnGraph::<(), f64>::with_capacity(n, 0);
+        let mut labels = Labels::new();
+        for (i, row) in rows.iter().enumerate() {
+            ensure!(row.len() == n, "row {} has {} entries, expected {}", i, row.len(), n);
+            graph.add_node(());
+            labels.intern(&i.to_string());
+        }
+
+        for (i, row) in rows.iter().enumerate() {
+            for (j, &w) in row.iter().enumerate().skip(i) {
+                ensure!(w == rows[j][i], "the matrix is not symmetric at ({}, {})", i, j);
+                ensure!(w.is_finite() && w >= 0., "weight must be finite and not negative, found {}", w);
+                if w > 0. {
+                    graph.add_edge(NodeIndex::new(i), NodeIndex::new(j), w);
+                }
+            }
+        }
+
+        Ok(Self { graph, labels })
+    }
+
+    /// Read a file of an adjacency matrix, see [`from_matrix`](LabeledGraph::from_matrix)
+    pub fn read_matrix<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_matrix(_open(path.as_ref())?)
+    }
+
+    /// Labels of the nodes of every cluster
+    pub fn cluster_labels<'a>(&'a self, clustering: &Clustering) -> Vec<Vec<&'a str>> {
+        clustering.iter()
+            .map(|c| c.iter().filter_map(|&n| self.labels.label(n)).collect())
+            .collect()
+    }
+
+    /// Write one cluster per line with tab-separated labels
+    pub fn write_clusters<W: Write>(&self, clustering: &Clustering, mut writer: W) -> Result<()> {
+        ensure!(clustering.node_count() <= self.labels.len(), "the clustering has more nodes than the graph");
+        for cluster in self.cluster_labels(clustering) {
+            writeln!(writer, "{}", cluster.join("\t"))?;
+        }
+        Ok(())
+    }
+}
+
+fn _open(path: &Path) -> Result<BufReader<File>> {
+    let file = File::open(path).with_context(|| format!("cannot open {}", path.display()))?;
+    Ok(BufReader::new(file))
+}
+
+/// Node of `label`, added to the graph if the label is new
+fn _node(graph: &mut UnGraph<(), f64>, labels: &mut Labels, label: &str) -> NodeIndex {
+    let i = labels.intern(label);
+    if i == graph.node_count() {
+        graph.add_node(());
+    }
+    NodeIndex::new(i)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_labels() {
+        let mut labels = Labels::new();
+        assert_eq!(labels.intern("a"), 0);
+        assert_eq!(labels.intern("b"), 1);
+        assert_eq!(labels.intern("a"), 0);
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels.label(1), Some("b"));
+        assert_eq!(labels.index("c"), None);
+    }
+
+    #[test]
+    fn test_from_abc() {
+        let labeled = LabeledGraph::from_abc("a b 2\n\n# comment\nb  c\nc a 0.5\na a\n".as_bytes()).unwrap();
+        assert_eq!(labeled.graph.node_count(), 3);
+        assert_eq!(labeled.graph.edge_count(), 4);
+        assert_eq!(labeled.labels.labels(), &["a", "b", "c"]);
+
+        let total: f64 = labeled.graph.raw_edges().iter().map(|e| e.weight).sum();
+        assert_eq!(total, 4.5);
+
+        // tabs separate labels containing spaces
+        let labeled = LabeledGraph::from_abc("protein A\tprotein B\t2\nprotein B\tc\n".as_bytes()).unwrap();
+        assert_eq!(labeled.labels.labels(), &["protein A", "protein B", "c"]);
+        assert_eq!(labeled.graph.edge_count(), 2);
+
+        assert!(LabeledGraph::from_abc("a\n".as_bytes()).is_err());
+        assert!(LabeledGraph::from_abc("a b c d\n".as_bytes()).is_err());
+        assert!(LabeledGraph::from_abc("a b x\n".as_bytes()).is_err());
+        assert!(LabeledGraph::from_abc("a b -1\n".as_bytes()).is_err());
+        assert!(LabeledGraph::from_abc("a\t\t1.0\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_from_edge_list() {
+        let labeled = LabeledGraph::from_edge_list("3 1 2\n1 0\n".as_bytes()).unwrap();
+        assert_eq!(labeled.graph.node_count(), 4);
+        assert_eq!(labeled.labels.labels(), &["0", "1", "2", "3"]);
+        assert!(labeled.graph.find_edge(NodeIndex::new(1), NodeIndex::new(3)).is_some());
+        assert_eq!(labeled.graph.neighbors(NodeIndex::new(2)).count(), 0);
+
+        assert!(LabeledGraph::from_edge_list("a 1\n".as_bytes()).is_err());
+        assert!(LabeledGraph::from_edge_list("0 4000000000\n".as_bytes()).is_err());
+        assert!(LabeledGraph::from_edge_list(format!("0 {}\n", usize::MAX).as_bytes()).is_err());
+        assert!(LabeledGraph::from_edge_list("0 100000000\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_from_matrix() {
+        let labeled = LabeledGraph::from_matrix("1 2 0\n2 0 1\n0 1 0\n".as_bytes()).unwrap();
+        assert_eq!(labeled.graph.node_count(), 3);
+        assert_eq!(labeled.graph.edge_count(), 3);
+        assert_eq!(labeled.labels.label(2), Some("2"));
+
+        assert!(LabeledGraph::from_matrix("0 1\n0 0\n".as_bytes()).is_err());
+        assert!(LabeledGraph::from_matrix("0 1\n1\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_write_clusters() {
+        let labeled = LabeledGraph::from_abc("x y\ny z\nw v\n".as_bytes()).unwrap();
+        let clustering = Clustering::new(5, vec![vec![0, 1, 2], vec![3, 4]]).unwrap();
+        assert_eq!(labeled.cluster_labels(&clustering), vec![vec!["x", "y", "z"], vec!["w", "v"]]);
+
+        let mut out = Vec::new();
+        labeled.write_clusters(&clustering, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "x\ty\tz\nw\tv\n");
+
+        let larger = Clustering::new(6, vec![vec![5]]).unwrap();
+        assert!(labeled.write_clusters(&larger, Vec::new()).is_err());
+    }
+}
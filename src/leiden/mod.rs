@@ -0,0 +1,457 @@
+/*!
+The Leiden algorithm improves the Louvain method so that every community is connected. It was developed by Traag et al.,
+details of the algorithm can be found in [From Louvain to Leiden: guaranteeing well-connected communities](https://doi.org/10.1038/s41598-019-41695-z).
+
+Each level runs three phases:
+
+1. fast local moving: nodes are moved to the community with the largest quality gain, revisiting only the neighbors of moved nodes
+2. refinement: every community is split into well-connected subcommunities, merging singletons at random with a probability growing with the quality gain
+3. aggregation: the subcommunities become the nodes of the next level, starting from the communities of the local moving phase
+
+Both [`Quality::Modularity`] and the Constant Potts Model [`Quality::Cpm`] can be optimized.
+Edge directions are ignored, and edge weights must not be negative.
+
+# Example Usage
+
+```rust
+use graph_clustering_rs::leiden::*;
+use petgraph::graph::UnGraph;
+
+// a 4-clique and a triangle joined by the edge 3-4, and the path 7-8-9 hanging from 6
+let graph = UnGraph::<f64, f64>::from_edges(&[
+    (0, 1, 1.), (0, 2, 1.), (0, 3, 1.), (1, 2, 1.), (1, 3, 1.), (2, 3, 1.),
+    (3, 4, 1.),
+    (4, 5, 1.), (4, 6, 1.), (5, 6, 1.),
+    (6, 7, 1.), (7, 8, 1.), (8, 9, 1.),
+]);
+
+let params = LeidenParams::builder().seed(Some(1)).build().unwrap();
+let res = graph.leiden(&params).unwrap();
+assert_eq!(res.clusters(), vec![vec![0, 1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]);
+
+// the resolution of the Constant Potts Model is the density a community needs,
+// so a low resolution merges the path into the triangle and a high one splits the path
+let cpm = |resolution| {
+    let params = LeidenParams::builder().quality(Quality::Cpm).resolution(resolution).build().unwrap();
+    graph.leiden(&params).unwrap().clusters()
+};
+assert_eq!(cpm(0.05), vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7, 8, 9]]);
+assert_eq!(cpm(0.9), vec![vec![0, 1, 2, 3], vec![4, 5, 6], vec![7, 8], vec![9]]);
+```
+*/
+
+use std::collections::VecDeque;
+
+use anyhow::Result;
+use num_traits::Float;
+use petgraph::{EdgeType, graph::{Graph, IndexType}};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::clustering::Clustering;
+use crate::network::{clusters_of, renumber, Network};
+
+pub mod params;
+
+pub use params::{LeidenParams, LeidenParamsBuilder, Quality};
+
+/// Result of a Leiden run
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeidenResult {
+    /// Community of every node
+    pub membership: Vec<usize>,
+    /// Value of the quality function of `membership`
+    pub quality: f64,
+    /// Number of iterations that were run
+    pub iterations: usize,
+}
+
+impl LeidenResult {
+    /// Nodes of every community
+    pub fn clusters(&self) -> Clustering {
+        Clustering::from_membership(&self.membership)
+    }
+}
+
+pub trait LeidenExt {
+    /// Find well-connected communities with the Leiden algorithm
+    fn leiden(&self, params: &LeidenParams) -> Result<LeidenResult>;
+}
+
+impl<N, E, Ty, Ix> LeidenExt for Graph<N, E, Ty, Ix>
+where
+    E: Float,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    fn leiden(&self, params: &LeidenParams) -> Result<LeidenResult> {
+        params.validate()?;
+
+        let original = Network::from_graph(self)?;
+        let mut rng = params.seed.map(StdRng::seed_from_u64);
+
+        let mut membership: Vec<usize> = (0..original.node_count()).collect();
+        let mut iterations = 0;
+
+        loop {
+            let next = _leiden_once(&original, &membership, params, &mut rng);
+            iterations += 1;
+
+            let stable = next == membership;
+            membership = next;
+            if stable || params.iterations.is_some_and(|n| iterations >= n) {
+                break;
+            }
+        }
+
+        let quality = match params.quality {
+            Quality::Modularity => original.modularity(&membership, params.resolution),
+            Quality::Cpm => original.cpm(&membership, params.resolution),
+        };
+
+        Ok(LeidenResult { membership, quality, iterations })
+    }
+}
+
+/// Quality gain of moving a node of weight `a` with edges of weight `w` into a community of total weight `total`
+/// is `w - penalty * a * total`, which is multiplied by `norm` to get the change of the quality function.
+struct _Objective {
+    weights: Vec<f64>,
+    penalty: f64,
+    norm: f64,
+}
+
+impl _Objective {
+    fn new(network: &Network, params: &LeidenParams) -> Self {
+        match params.quality {
+            Quality::Modularity => {
+                let m = network.total_weight;
+                let (penalty, norm) = if m > 0. { (params.resolution / (2. * m), 1. / m) } else { (0., 1.) };
+                Self { weights: network.strength.clone(), penalty, norm }
+            },
+            Quality::Cpm => Self { weights: network.sizes.clone(), penalty: params.resolution, norm: 1. },
+        }
+    }
+
+    fn gain(&self, w: f64, a: f64, total: f64) -> f64 {
+        w - self.penalty * a * total
+    }
+}
+
+/// One iteration of the Leiden algorithm starting from `initial`, returning the renumbered communities of the original nodes
+fn _leiden_once(original: &Network, initial: &[usize], params: &LeidenParams, rng: &mut Option<StdRng>) -> Vec<usize> {
+    let mut network = original.clone();
+    let mut membership = initial.to_vec();
+    // aggregate node of every original node
+    let mut node_of: Vec<usize> = (0..original.node_count()).collect();
+
+    loop {
+        let objective = _Objective::new(&network, params);
+        _move_nodes_fast(&network, &objective, &mut membership, rng);
+        let n_communities = renumber(&mut membership);
+
+        if n_communities == network.node_count() {
+            break;
+        }
+
+        let mut refined = _refine(&network, &objective, &membership, params.randomness, rng);
+        let mut n_refined = renumber(&mut refined);
+
+        // aggregate by the communities if the refinement did not merge anything, otherwise the level would repeat
+        if n_refined == network.node_count() {
+            refined = membership.clone();
+            n_refined = n_communities;
+        }
+
+        let mut parent = vec![0; n_refined];
+        for (i, &r) in refined.iter().enumerate() {
+            parent[r] = membership[i];
+        }
+
+        node_of.iter_mut().for_each(|v| *v = refined[*v]);
+        network = network.aggregate(&refined, n_refined);
+        membership = parent;
+    }
+
+    let mut result: Vec<usize> = node_of.iter().map(|&v| membership[v]).collect();
+    renumber(&mut result);
+    result
+}
+
+/// Move nodes to the community with the largest gain, visiting the nodes in a queue to which the neighbors of moved nodes are added again
+fn _move_nodes_fast(network: &Network, objective: &_Objective, membership: &mut [usize], rng: &mut Option<StdRng>) {
+    let n = network.node_count();
+    let a = &objective.weights;
+
+    let mut total = vec![0.; n];
+    let mut count = vec![0usize; n];
+    for (i, &c) in membership.iter().enumerate() {
+        total[c] += a[i];
+        count[c] += 1;
+    }
+    let mut empty: Vec<usize> = (0..n).filter(|&c| count[c] == 0).collect();
+
+    let mut order: Vec<usize> = (0..n).collect();
+    if let Some(rng) = rng {
+        order.shuffle(rng);
+    }
+    let mut queue: VecDeque<usize> = order.into_iter().collect();
+    let mut in_queue = vec![true; n];
+
+    let mut weights = vec![0.; n];
+    let mut touched = vec![false; n];
+    let mut neighbors: Vec<usize> = Vec::new();
+
+    while let Some(v) = queue.pop_front() {
+        in_queue[v] = false;
+        let c = membership[v];
+
+        neighbors.push(c);
+        touched[c] = true;
+        for &(u, w) in network.adj[v].iter() {
+            let d = membership[u];
+            if !touched[d] {
+                touched[d] = true;
+                neighbors.push(d);
+            }
+            weights[d] += w;
+        }
+
+        total[c] -= a[v];
+        count[c] -= 1;
+        if count[c] == 0 {
+            empty.push(c);
+        }
+
+        let (mut best, mut best_gain) = (c, objective.gain(weights[c], a[v], total[c]));
+        for &d in neighbors.iter() {
+            let g = objective.gain(weights[d], a[v], total[d]);
+            if g > best_gain {
+                best = d;
+                best_gain = g;
+            }
+        }
+        // an empty community gains nothing, which is better than a negative gain
+        if best_gain < 0. {
+            if let Some(&e) = empty.last() {
+                best = e;
+            }
+        }
+
+        total[best] += a[v];
+        count[best] += 1;
+        if count[best] == 1 {
+            // only the last empty community can be chosen
+            empty.pop();
+        }
+        membership[v] = best;
+
+        if best != c {
+            for &(u, _) in network.adj[v].iter() {
+                if !in_queue[u] && membership[u] != best {
+                    in_queue[u] = true;
+                    queue.push_back(u);
+                }
+            }
+        }
+
+        for &d in neighbors.iter() {
+            weights[d] = 0.;
+            touched[d] = false;
+        }
+        neighbors.clear();
+    }
+}
+
+/// Split every community into well-connected subcommunities by merging singletons within the community
+fn _refine(network: &Network, objective: &_Objective, membership: &[usize], randomness: f64, rng: &mut Option<StdRng>) -> Vec<usize> {
+    let n = network.node_count();
+    let a = &objective.weights;
+
+    let mut refined: Vec<usize> = (0..n).collect();
+    let mut size = vec![1usize; n];
+    let mut total = a.clone();
+
+    // weight from every node, and then from every subcommunity, to the rest of its community
+    let inner: Vec<f64> = (0..n)
+        .map(|v| network.adj[v].iter().filter(|&&(u, _)| membership[u] == membership[v]).map(|&(_, w)| w).sum())
+        .collect();
+    let mut external = inner.clone();
+
+    let mut weights = vec![0.; n];
+    let mut touched = vec![false; n];
+    let mut neighbors: Vec<usize> = Vec::new();
+    let mut candidates: Vec<(usize, f64)> = Vec::new();
+
+    for mut community in clusters_of(membership) {
+        let community_total: f64 = community.iter().map(|&v| a[v]).sum();
+        if let Some(rng) = rng {
+            community.shuffle(rng);
+        }
+
+        for &v in community.iter() {
+            let r = refined[v];
+            if size[r] != 1 || inner[v] < objective.penalty * a[v] * (community_total - a[v]) {
+                continue;
+            }
+
+            for &(u, w) in network.adj[v].iter() {
+                if membership[u] != membership[v] { continue; }
+                let d = refined[u];
+                if !touched[d] {
+                    touched[d] = true;
+                    neighbors.push(d);
+                }
+                weights[d] += w;
+            }
+
+            // staying alone gains nothing
+            candidates.push((r, 0.));
+            for &d in neighbors.iter() {
+                let well_connected = external[d] >= objective.penalty * total[d] * (community_total - total[d]);
+                let g = objective.gain(weights[d], a[v], total[d]);
+                if d != r && well_connected && g >= 0. {
+                    candidates.push((d, g));
+                }
+            }
+
+            let chosen = match rng {
+                Some(rng) => _sample(&candidates, objective.norm / randomness, rng),
+                None => _argmax(&candidates),
+            };
+
+            if chosen != r {
+                refined[v] = chosen;
+                size[r] = 0;
+                size[chosen] += 1;
+                total[chosen] += a[v];
+                external[chosen] += inner[v] - 2. * weights[chosen];
+            }
+
+            for &d in neighbors.iter() {
+                weights[d] = 0.;
+                touched[d] = false;
+            }
+            neighbors.clear();
+            candidates.clear();
+        }
+    }
+
+    refined
+}
+
+/// Candidate with the largest gain, the first one on ties
+fn _argmax(candidates: &[(usize, f64)]) -> usize {
+    let mut best = candidates[0];
+    for &c in candidates[1..].iter() {
+        if c.1 > best.1 {
+            best = c;
+        }
+    }
+    best.0
+}
+
+/// Candidate drawn with a probability proportional to `exp(gain * scale)`
+fn _sample(candidates: &[(usize, f64)], scale: f64, rng: &mut StdRng) -> usize {
+    let max = candidates.iter().map(|c| c.1).fold(f64::NEG_INFINITY, f64::max);
+    let probs: Vec<f64> = candidates.iter().map(|c| ((c.1 - max) * scale).exp()).collect();
+
+    let mut x = rng.gen::<f64>() * probs.iter().sum::<f64>();
+    for (c, p) in candidates.iter().zip(probs.iter()) {
+        if x < *p {
+            return c.0;
+        }
+        x -= p;
+    }
+    candidates[candidates.len() - 1].0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+    use petgraph::graph::UnGraph;
+
+    fn random_graph(n: u32, p: f64, seed: u64) -> UnGraph<(), f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut edges = Vec::new();
+        for a in 0..n {
+            for b in (a + 1)..n {
+                if rng.gen::<f64>() < p {
+                    edges.push((a, b, 1.));
+                }
+            }
+        }
+        let mut graph = UnGraph::from_edges(edges);
+        while graph.node_count() < n as usize {
+            graph.add_node(());
+        }
+        graph
+    }
+
+    fn is_connected(graph: &UnGraph<(), f64>, nodes: &[usize]) -> bool {
+        let members: HashSet<usize> = nodes.iter().copied().collect();
+        let mut seen: HashSet<usize> = HashSet::new();
+        let mut stack = vec![nodes[0]];
+        while let Some(v) = stack.pop() {
+            if !seen.insert(v) { continue; }
+            for u in graph.neighbors(petgraph::graph::NodeIndex::new(v)) {
+                if members.contains(&u.index()) {
+                    stack.push(u.index());
+                }
+            }
+        }
+        seen.len() == nodes.len()
+    }
+
+    #[test]
+    fn test_connected_communities() {
+        for seed in 0..10 {
+            let graph = random_graph(60, 0.08, seed);
+            let params = LeidenParams::builder().seed(Some(seed)).build().unwrap();
+            let res = graph.leiden(&params).unwrap();
+
+            for cluster in res.clusters() {
+                assert!(is_connected(&graph, &cluster));
+            }
+            assert!(res.quality > 0.);
+        }
+    }
+
+    #[test]
+    fn test_seed() {
+        let graph = random_graph(80, 0.05, 3);
+        let params = LeidenParams::builder().seed(Some(11)).iterations(None).build().unwrap();
+        assert_eq!(graph.leiden(&params).unwrap(), graph.leiden(&params).unwrap());
+    }
+
+    #[test]
+    fn test_not_worse_than_louvain() {
+        use crate::louvain::{LouvainExt, LouvainParams};
+
+        let graph = random_graph(100, 0.05, 5);
+        let louvain = graph.louvain(&LouvainParams::default()).unwrap();
+        let leiden = graph.leiden(&LeidenParams::builder().iterations(None).build().unwrap()).unwrap();
+        assert!(leiden.quality > louvain.modularity - 0.02);
+    }
+
+    #[test]
+    fn test_cpm_resolution() {
+        // a triangle and a separate edge
+        let graph = UnGraph::<(), f64>::from_edges([(0, 1, 1.), (1, 2, 1.), (0, 2, 1.), (3, 4, 1.)]);
+
+        let params = LeidenParams::builder().quality(Quality::Cpm).resolution(0.).build().unwrap();
+        let res = graph.leiden(&params).unwrap();
+        assert_eq!(res.clusters(), vec![vec![0, 1, 2], vec![3, 4]]);
+        assert_eq!(res.quality, 4.);
+
+        let params = LeidenParams::builder().quality(Quality::Cpm).resolution(1.5).build().unwrap();
+        assert_eq!(graph.leiden(&params).unwrap().clusters().len(), 5);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let graph = UnGraph::<(), f64>::from_edges([(0, 1, f64::NAN)]);
+        assert!(graph.leiden(&LeidenParams::default()).is_err());
+        assert!(LeidenParams::builder().iterations(Some(0)).build().is_err());
+    }
+}
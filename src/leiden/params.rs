@@ -0,0 +1,128 @@
+/*!
+Parameters of [`LeidenExt::leiden`](super::LeidenExt::leiden).
+
+The defaults follow the reference implementation: modularity with resolution 1, randomness 0.01 and two iterations.
+
+```rust
+use graph_clustering_rs::leiden::*;
+
+let params = LeidenParams::builder()
+    .quality(Quality::Cpm)
+    .resolution(0.05)
+    .seed(Some(42))
+    .iterations(None)
+    .build()
+    .unwrap();
+assert_eq!(params.randomness, 0.01);
+
+assert!(LeidenParams::builder().randomness(0.).build().is_err());
+```
+*/
+
+use anyhow::{ensure, Result};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Quality function optimized by the Leiden algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Quality {
+    /// Modularity, the resolution scales the expected weight of the configuration model
+    #[default]
+    Modularity,
+    /// Constant Potts Model, the resolution is the density a community must at least have
+    Cpm,
+}
+
+/// Parameters of the Leiden algorithm
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct LeidenParams {
+    /// Quality function
+    pub quality: Quality,
+    /// Resolution of the quality function, larger values give smaller communities
+    pub resolution: f64,
+    /// Randomness of the merges in the refinement phase, smaller values make them greedier
+    pub randomness: f64,
+    /// Seed of the random node order and refinement, nodes are visited in index order and merged greedily if `None`
+    pub seed: Option<u64>,
+    /// Number of iterations, iterate until the partition does not change if `None`
+    pub iterations: Option<usize>,
+}
+
+impl Default for LeidenParams {
+    fn default() -> Self {
+        Self {
+            quality: Quality::Modularity,
+            resolution: 1.,
+            randomness: 0.01,
+            seed: None,
+            iterations: Some(2),
+        }
+    }
+}
+
+impl LeidenParams {
+    /// Start building parameters from the defaults
+    pub fn builder() -> LeidenParamsBuilder {
+        LeidenParamsBuilder {
+            params: Self::default(),
+        }
+    }
+
+    /// Check that the parameters can be used to run the Leiden algorithm
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.resolution.is_finite() && self.resolution >= 0., "resolution must not be negative");
+        ensure!(self.randomness.is_finite() && self.randomness > 0., "randomness must be positive");
+        if let Some(iterations) = self.iterations {
+            ensure!(iterations > 0, "iterations must be at least 1");
+        }
+        Ok(())
+    }
+}
+
+/// Builder of [`LeidenParams`]
+#[derive(Debug, Clone)]
+pub struct LeidenParamsBuilder {
+    params: LeidenParams,
+}
+
+impl LeidenParamsBuilder {
+    /// Set the quality function
+    pub fn quality(mut self, quality: Quality) -> Self {
+        self.params.quality = quality;
+        self
+    }
+
+    /// Set the resolution of the quality function
+    pub fn resolution(mut self, resolution: f64) -> Self {
+        self.params.resolution = resolution;
+        self
+    }
+
+    /// Set the randomness of the merges in the refinement phase
+    pub fn randomness(mut self, randomness: f64) -> Self {
+        self.params.randomness = randomness;
+        self
+    }
+
+    /// Set the seed of the random node order and refinement
+    pub fn seed(mut self, seed: Option<u64>) -> Self {
+        self.params.seed = seed;
+        self
+    }
+
+    /// Set the number of iterations, `None` iterates until the partition does not change
+    pub fn iterations(mut self, iterations: Option<usize>) -> Self {
+        self.params.iterations = iterations;
+        self
+    }
+
+    /// Validate and return the parameters
+    pub fn build(self) -> Result<LeidenParams> {
+        self.params.validate()?;
+        Ok(self.params)
+    }
+}
@@ -6,13 +6,33 @@ Please see the [API documentation](https://illumination-k.github.io/graph-cluste
 ## RoadMap
 
 - [x] Markov Clustering
-- [ ] louvain
-- [ ] HCCA
-- [ ] MCODE
-- [ ] DPClus
-- [ ] IPCA
-- [ ] CoAch
-- [ ] Graph Entropy Clustering
+- [x] louvain
+- [x] leiden
+- [x] HCCA
+- [x] MCODE
+- [x] DPClus
+- [x] IPCA
+- [x] CoAch
+- [x] Graph Entropy Clustering
+
+## Serialization
+
+With the `serde` feature, the parameters of every algorithm, such as [`MclParams`](mcl::MclParams), can be serialized and
+deserialized, and missing fields take their default values. [`Algorithm`](clusterer::Algorithm) reads the algorithm and its parameters
+from one object.
+
+## Command line
+
+The optional `cli` feature builds the `graph-clustering` binary with a subcommand for every algorithm,
+so that library users do not depend on clap. Install it with `cargo install graph-clustering-rs --features cli`.
+It reads an ABC file (`--format abc`), an edge list of integer IDs (`edges`) or an adjacency matrix (`matrix`),
+and writes one cluster per line with tab-separated labels, as `mcl --abc` does. A summary of the run is printed to stderr.
+
+```text
+graph-clustering mcl network.abc -I 1.4 -o clusters.txt
+graph-clustering louvain edges.txt --format edges --resolution 0.5
+graph-clustering mcode --help
+```
 */
 
 
@@ -27,6 +47,31 @@ extern crate num_traits;
 
 extern crate petgraph;
 
+extern crate rayon;
+
+extern crate rand;
+
+#[cfg(feature = "serde")]
+extern crate serde;
+
 pub mod mcl;
 pub mod utils;
-pub mod mcode;
\ No newline at end of file
+pub mod mcode;
+pub mod observer;
+pub mod louvain;
+pub mod leiden;
+pub mod hcca;
+pub mod dpclus;
+pub mod ipca;
+pub mod coach;
+pub mod graph_entropy;
+pub mod clustering;
+pub mod clusterer;
+pub mod labeled;
+pub mod metrics;
+pub mod compare;
+pub mod benchmark;
+
+mod graph_utils;
+mod network;
+mod union_find;
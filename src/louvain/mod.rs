@@ -0,0 +1,289 @@
+/*!
+The Louvain method finds communities by greedy modularity optimization. It was developed by Blondel et al.,
+details of the algorithm can be found in [Fast unfolding of communities in large networks](https://doi.org/10.1088/1742-5468/2008/10/P10008).
+
+Each level moves single nodes to the neighboring community with the largest modularity gain until no move improves the modularity,
+and then aggregates the communities into the nodes of the next level. The run stops when a level does not merge any node.
+Every level is kept in the returned [`Dendrogram`].
+
+Edge directions are ignored, and edge weights must not be negative.
+
+# Example Usage
+
+```rust
+use graph_clustering_rs::louvain::*;
+use petgraph::graph::UnGraph;
+
+// two 4-cliques joined by the edge 3-4
+let graph = UnGraph::<(), f64>::from_edges(&[
+    (0, 1, 1.), (0, 2, 1.), (0, 3, 1.), (1, 2, 1.), (1, 3, 1.), (2, 3, 1.),
+    (3, 4, 1.),
+    (4, 5, 1.), (4, 6, 1.), (4, 7, 1.), (5, 6, 1.), (5, 7, 1.), (6, 7, 1.),
+]);
+
+let res = graph.louvain(&LouvainParams::default()).unwrap();
+assert_eq!(res.membership, vec![0, 0, 0, 0, 1, 1, 1, 1]);
+assert_eq!(res.clusters(), vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7]]);
+assert!((res.modularity - (12. / 13. - 0.5)).abs() < 1e-12);
+```
+*/
+
+use anyhow::Result;
+use petgraph::{EdgeType, graph::{Graph, IndexType}};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::clustering::Clustering;
+use crate::network::{renumber, Network};
+
+pub mod params;
+
+pub use params::{LouvainParams, LouvainParamsBuilder};
+
+/// Communities of every level of a Louvain run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dendrogram {
+    /// `levels[0]` maps the nodes of the graph to the communities of the first level,
+    /// and `levels[l]` maps the communities of level `l - 1` to those of level `l`
+    pub levels: Vec<Vec<usize>>,
+}
+
+impl Dendrogram {
+    /// Number of levels
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    /// Community of every node of the graph at `level`, `None` if there is no such level
+    pub fn partition(&self, level: usize) -> Option<Vec<usize>> {
+        self.levels.get(level)?;
+
+        let mut membership = self.levels[0].clone();
+        for l in self.levels[1..=level].iter() {
+            membership.iter_mut().for_each(|c| *c = l[*c]);
+        }
+        Some(membership)
+    }
+}
+
+/// Result of a Louvain run
+#[derive(Debug, Clone, PartialEq)]
+pub struct LouvainResult {
+    /// Communities of every level
+    pub dendrogram: Dendrogram,
+    /// Community of every node at the last level
+    pub membership: Vec<usize>,
+    /// Modularity of `membership` with the resolution of the run
+    pub modularity: f64,
+}
+
+impl LouvainResult {
+    /// Nodes of every community at the last level
+    pub fn clusters(&self) -> Clustering {
+        Clustering::from_membership(&self.membership)
+    }
+}
+
+pub trait LouvainExt {
+    /// Find communities with the Louvain method
+    fn louvain(&self, params: &LouvainParams) -> Result<LouvainResult>;
+}
+
+impl<N, Ty, Ix> LouvainExt for Graph<N, f64, Ty, Ix>
+where
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    fn louvain(&self, params: &LouvainParams) -> Result<LouvainResult> {
+        params.validate()?;
+
+        let original = Network::from_graph(self)?;
+        let mut rng = params.seed.map(StdRng::seed_from_u64);
+
+        let mut network = original.clone();
+        let mut levels: Vec<Vec<usize>> = Vec::new();
+
+        loop {
+            let mut membership: Vec<usize> = (0..network.node_count()).collect();
+            _move_nodes(&network, &mut membership, params, &mut rng);
+            let n_communities = renumber(&mut membership);
+
+            let merged = n_communities < network.node_count();
+            if merged || levels.is_empty() {
+                levels.push(membership.clone());
+            }
+            if !merged {
+                break;
+            }
+
+            network = network.aggregate(&membership, n_communities);
+        }
+
+        let dendrogram = Dendrogram { levels };
+        let membership = dendrogram.partition(dendrogram.len() - 1).unwrap();
+        let modularity = original.modularity(&membership, params.resolution);
+
+        Ok(LouvainResult { dendrogram, membership, modularity })
+    }
+}
+
+/// Move single nodes to the neighboring community with the largest modularity gain until a pass gains less than the tolerance
+fn _move_nodes(network: &Network, membership: &mut [usize], params: &LouvainParams, rng: &mut Option<StdRng>) {
+    let n = network.node_count();
+    let m = network.total_weight;
+    if m == 0. {
+        return;
+    }
+
+    // total strength of every community
+    let mut tot = vec![0.; n];
+    for (i, &c) in membership.iter().enumerate() {
+        tot[c] += network.strength[i];
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    if let Some(rng) = rng {
+        order.shuffle(rng);
+    }
+
+    // weight from the current node to every neighboring community
+    let mut weights = vec![0.; n];
+    let mut touched = vec![false; n];
+    let mut neighbors: Vec<usize> = Vec::new();
+
+    loop {
+        let mut improvement = 0.;
+
+        for &i in order.iter() {
+            let c = membership[i];
+            let k = network.strength[i];
+
+            neighbors.push(c);
+            touched[c] = true;
+            for &(j, w) in network.adj[i].iter() {
+                let d = membership[j];
+                if !touched[d] {
+                    touched[d] = true;
+                    neighbors.push(d);
+                }
+                weights[d] += w;
+            }
+
+            tot[c] -= k;
+            let gain = |d: usize| weights[d] - params.resolution * k * tot[d] / (2. * m);
+
+            // the current community comes first, so ties keep the node where it is
+            let (mut best, mut best_gain) = (c, gain(c));
+            for &d in neighbors.iter() {
+                let g = gain(d);
+                if g > best_gain {
+                    best = d;
+                    best_gain = g;
+                }
+            }
+            improvement += (best_gain - gain(c)) / m;
+
+            tot[best] += k;
+            membership[i] = best;
+
+            for &d in neighbors.iter() {
+                weights[d] = 0.;
+                touched[d] = false;
+            }
+            neighbors.clear();
+        }
+
+        if improvement <= params.tolerance {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use petgraph::graph::UnGraph;
+
+    /// `n` 4-cliques joined in a ring
+    fn ring_of_cliques(n: usize) -> UnGraph<(), f64> {
+        let mut edges = Vec::new();
+        for c in 0..n {
+            let base = (4 * c) as u32;
+            for a in 0..4 {
+                for b in (a + 1)..4 {
+                    edges.push((base + a, base + b, 1.));
+                }
+            }
+            edges.push((base + 3, ((4 * (c + 1)) % (4 * n)) as u32, 1.));
+        }
+        UnGraph::from_edges(&edges)
+    }
+
+    #[test]
+    fn test_ring_of_cliques() {
+        let graph = ring_of_cliques(6);
+        let res = graph.louvain(&LouvainParams::default()).unwrap();
+
+        // no community splits a clique
+        for c in 0..6 {
+            assert!(res.membership[4 * c..4 * c + 4].iter().all(|&x| x == res.membership[4 * c]));
+        }
+        assert_eq!(res.dendrogram.partition(res.dendrogram.len() - 1).unwrap(), res.membership);
+        assert!(res.dendrogram.partition(res.dendrogram.len()).is_none());
+        assert!(res.modularity > 0.6);
+    }
+
+    #[test]
+    fn test_dendrogram() {
+        let graph = ring_of_cliques(30);
+        let res = graph.louvain(&LouvainParams::default()).unwrap();
+
+        assert!(res.dendrogram.len() > 1);
+        let mut n_communities = graph.node_count();
+        for level in 0..res.dendrogram.len() {
+            let partition = res.dendrogram.partition(level).unwrap();
+            assert_eq!(partition.len(), graph.node_count());
+            assert_eq!(res.dendrogram.levels[level].len(), n_communities);
+            n_communities = partition.iter().max().unwrap() + 1;
+        }
+    }
+
+    #[test]
+    fn test_seed() {
+        let graph = ring_of_cliques(8);
+        let params = LouvainParams::builder().seed(Some(7)).build().unwrap();
+        assert_eq!(graph.louvain(&params).unwrap(), graph.louvain(&params).unwrap());
+    }
+
+    #[test]
+    fn test_resolution() {
+        let graph = ring_of_cliques(4);
+
+        let fine = graph.louvain(&LouvainParams::builder().resolution(100.).build().unwrap()).unwrap();
+        assert_eq!(fine.clusters().len(), 16);
+        assert_eq!(fine.dendrogram.len(), 1);
+
+        let coarse = graph.louvain(&LouvainParams::builder().resolution(0.).build().unwrap()).unwrap();
+        assert_eq!(coarse.clusters().len(), 1);
+    }
+
+    #[test]
+    fn test_without_edges() {
+        let mut graph = UnGraph::<(), f64>::new_undirected();
+        graph.add_node(());
+        graph.add_node(());
+
+        let res = graph.louvain(&LouvainParams::default()).unwrap();
+        assert_eq!(res.membership, vec![0, 1]);
+        assert_eq!(res.modularity, 0.);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let graph = UnGraph::<(), f64>::from_edges([(0, 1, -1.)]);
+        assert!(graph.louvain(&LouvainParams::default()).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+/*!
+Parameters of [`LouvainExt::louvain`](super::LouvainExt::louvain).
+
+```rust
+use graph_clustering_rs::louvain::*;
+
+let params = LouvainParams::builder()
+    .resolution(0.5)
+    .seed(Some(42))
+    .build()
+    .unwrap();
+assert_eq!(params.tolerance, 1e-7);
+
+assert!(LouvainParams::builder().resolution(-1.).build().is_err());
+```
+*/
+
+use anyhow::{ensure, Result};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Parameters of the Louvain method
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct LouvainParams {
+    /// Resolution of the modularity, larger values give smaller communities
+    pub resolution: f64,
+    /// Seed of the random node order, nodes are visited in index order if `None`
+    pub seed: Option<u64>,
+    /// Minimum modularity gain of a pass over the nodes to run another pass
+    pub tolerance: f64,
+}
+
+impl Default for LouvainParams {
+    fn default() -> Self {
+        Self {
+            resolution: 1.,
+            seed: None,
+            tolerance: 1e-7,
+        }
+    }
+}
+
+impl LouvainParams {
+    /// Start building parameters from the defaults
+    pub fn builder() -> LouvainParamsBuilder {
+        LouvainParamsBuilder {
+            params: Self::default(),
+        }
+    }
+
+    /// Check that the parameters can be used to run the Louvain method
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.resolution.is_finite() && self.resolution >= 0., "resolution must not be negative");
+        ensure!(self.tolerance.is_finite() && self.tolerance >= 0., "tolerance must not be negative");
+        Ok(())
+    }
+}
+
+/// Builder of [`LouvainParams`]
+#[derive(Debug, Clone)]
+pub struct LouvainParamsBuilder {
+    params: LouvainParams,
+}
+
+impl LouvainParamsBuilder {
+    /// Set the resolution of the modularity
+    pub fn resolution(mut self, resolution: f64) -> Self {
+        self.params.resolution = resolution;
+        self
+    }
+
+    /// Set the seed of the random node order
+    pub fn seed(mut self, seed: Option<u64>) -> Self {
+        self.params.seed = seed;
+        self
+    }
+
+    /// Set the minimum modularity gain of a pass to run another pass
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.params.tolerance = tolerance;
+        self
+    }
+
+    /// Validate and return the parameters
+    pub fn build(self) -> Result<LouvainParams> {
+        self.params.validate()?;
+        Ok(self.params)
+    }
+}
@@ -0,0 +1,311 @@
+/*!
+MCL on petgraph graphs.
+
+[`MclGraphExt::mcl`] is available on [`Graph`], [`StableGraph`] and [`GraphMap`] with numeric edge weights. The graph is turned into a
+[`CscMatrix`] whose column `j` holds the weights of the edges leaving the `j`-th node, parallel edges are summed, and every edge of an
+undirected graph is used in both directions. Directed graphs are not symmetrized by [`MclGraphExt::mcl`], so the random walk follows the
+edge directions, and [`MclGraphExt::mcl_symmetrized`] makes every edge go both ways with a [`Symmetrize`] rule first.
+
+```rust
+use graph_clustering_rs::mcl::*;
+use petgraph::graph::{NodeIndex, UnGraph};
+
+// two triangles joined by the edge 2-3, the node weights are names
+let mut graph = UnGraph::<&str, f64>::new_undirected();
+let nodes: Vec<NodeIndex> = ["a", "b", "c", "d", "e", "f"].iter().map(|&name| graph.add_node(name)).collect();
+for &(a, b) in [(0, 1), (0, 2), (1, 2), (2, 3), (3, 4), (3, 5), (4, 5)].iter() {
+    graph.add_edge(nodes[a], nodes[b], 1.);
+}
+
+let res = graph.mcl(&MclParams::<f64>::default()).unwrap();
+assert_eq!(res.clusters.len(), 2);
+assert!(res.clusters[0].contains(&nodes[0]));
+assert_eq!(res.weight(nodes[5]), Some(&"f"));
+```
+*/
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::iter::Sum;
+
+use anyhow::{ensure, Result};
+use num_traits::{Float, ToPrimitive};
+use petgraph::{
+    EdgeType,
+    graph::{Graph, IndexType},
+    graphmap::{GraphMap, NodeTrait},
+    stable_graph::StableGraph,
+    visit::{EdgeRef, GraphProp, IntoEdgeReferences, IntoNodeReferences, NodeRef},
+};
+
+use super::{get_clusters, CscMatrix, MclExt, MclParams};
+
+/// Clusters found by MCL on a petgraph graph
+#[derive(Debug, Clone)]
+pub struct MclGraphClusters<Id, N> {
+    /// Nodes of every cluster, in the order of [`get_clusters`]
+    pub clusters: Vec<HashSet<Id>>,
+    /// Weight of every node of the graph
+    pub node_weights: HashMap<Id, N>,
+}
+
+impl<Id: Hash + Eq, N> MclGraphClusters<Id, N> {
+    /// Weight of `node`, `None` if it is not a node of the graph
+    pub fn weight(&self, node: Id) -> Option<&N> {
+        self.node_weights.get(&node)
+    }
+}
+
+/// How the edges of a directed graph are made undirected before running MCL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Symmetrize {
+    /// Keep the edge directions
+    #[default]
+    None,
+    /// Weight both directions between two nodes with the sum of their weights
+    Add,
+    /// Weight both directions between two nodes with the larger of their weights
+    Max,
+}
+
+pub trait MclGraphExt<Id, N> {
+    /// Run MCL on the weighted adjacency matrix of the graph, following the edge directions of a directed graph
+    ///
+    /// Edge weights must be finite and not negative.
+    fn mcl<A>(&self, params: &MclParams<A>) -> Result<MclGraphClusters<Id, N>>
+    where
+        A: 'static + Float + Sum + Send + Sync
+    {
+        self.mcl_symmetrized(params, Symmetrize::None)
+    }
+
+    /// Run MCL on the weighted adjacency matrix of the graph, symmetrized with `symmetrize` if the graph is directed
+    ///
+    /// Parallel edges are summed before the symmetrization. Self loops are kept only with a `params.loop_value` of 0, otherwise
+    /// the diagonal is replaced by `params.loop_value` as in [`MclExt::add_self_loop`].
+    ///
+    /// ```rust
+    /// use graph_clustering_rs::mcl::*;
+    /// use petgraph::graph::DiGraph;
+    ///
+    /// // two directed triangles joined by the edge 2->3
+    /// let graph = DiGraph::<(), f64>::from_edges([(0, 1, 1.), (1, 2, 1.), (2, 0, 1.), (2, 3, 1.), (3, 4, 1.), (4, 5, 1.), (5, 3, 1.)]);
+    /// let res = graph.mcl_symmetrized(&MclParams::<f64>::default(), Symmetrize::Max).unwrap();
+    /// assert_eq!(res.clusters.len(), 2);
+    /// ```
+    fn mcl_symmetrized<A>(&self, params: &MclParams<A>, symmetrize: Symmetrize) -> Result<MclGraphClusters<Id, N>>
+    where
+        A: 'static + Float + Sum + Send + Sync;
+}
+
+impl<N, E, Ty, Ix> MclGraphExt<petgraph::graph::NodeIndex<Ix>, N> for Graph<N, E, Ty, Ix>
+where
+    N: Clone,
+    E: ToPrimitive,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    fn mcl_symmetrized<A>(&self, params: &MclParams<A>, symmetrize: Symmetrize) -> Result<MclGraphClusters<petgraph::graph::NodeIndex<Ix>, N>>
+    where
+        A: 'static + Float + Sum + Send + Sync
+    {
+        _mcl_graph(self, params, symmetrize)
+    }
+}
+
+impl<N, E, Ty, Ix> MclGraphExt<petgraph::stable_graph::NodeIndex<Ix>, N> for StableGraph<N, E, Ty, Ix>
+where
+    N: Clone,
+    E: ToPrimitive,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    fn mcl_symmetrized<A>(&self, params: &MclParams<A>, symmetrize: Symmetrize) -> Result<MclGraphClusters<petgraph::stable_graph::NodeIndex<Ix>, N>>
+    where
+        A: 'static + Float + Sum + Send + Sync
+    {
+        _mcl_graph(self, params, symmetrize)
+    }
+}
+
+impl<N, E, Ty> MclGraphExt<N, N> for GraphMap<N, E, Ty>
+where
+    N: NodeTrait,
+    E: ToPrimitive,
+    Ty: EdgeType
+{
+    fn mcl_symmetrized<A>(&self, params: &MclParams<A>, symmetrize: Symmetrize) -> Result<MclGraphClusters<N, N>>
+    where
+        A: 'static + Float + Sum + Send + Sync
+    {
+        _mcl_graph(self, params, symmetrize)
+    }
+}
+
+fn _mcl_graph<G, E, A>(graph: G, params: &MclParams<A>, symmetrize: Symmetrize) -> Result<MclGraphClusters<G::NodeId, G::NodeWeight>>
+where
+    G: IntoNodeReferences + IntoEdgeReferences<EdgeWeight = E> + GraphProp,
+    G::NodeId: Hash + Eq,
+    G::NodeWeight: Clone,
+    E: ToPrimitive,
+    A: 'static + Float + Sum + Send + Sync
+{
+    let node_weights: HashMap<G::NodeId, G::NodeWeight> = graph.node_references()
+        .map(|n| (n.id(), n.weight().clone()))
+        .collect();
+    let (ids, matrix) = _adjacency(graph, symmetrize)?;
+    let clusters = get_clusters(&matrix.mcl(params)?)?;
+
+    Ok(MclGraphClusters {
+        clusters: clusters.into_iter().map(|c| c.into_iter().map(|i| ids[i]).collect()).collect(),
+        node_weights,
+    })
+}
+
+/// Node ids in matrix order and the matrix whose column `j` holds the weights of the edges leaving the `j`-th node
+fn _adjacency<G, E, A>(graph: G, symmetrize: Symmetrize) -> Result<(Vec<G::NodeId>, CscMatrix<A>)>
+where
+    G: IntoNodeReferences + IntoEdgeReferences<EdgeWeight = E> + GraphProp,
+    G::NodeId: Hash + Eq,
+    E: ToPrimitive,
+    A: Float + Send + Sync
+{
+    let ids: Vec<G::NodeId> = graph.node_references().map(|n| n.id()).collect();
+    let positions: HashMap<G::NodeId, usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+
+    let directed = graph.is_directed();
+    let mut triplets = Vec::new();
+    for e in graph.edge_references() {
+        let w = e.weight().to_f64().and_then(A::from).unwrap_or_else(A::nan);
+        ensure!(w.is_finite() && w >= A::zero(), "edge weights must be finite and not negative");
+
+        let (s, t) = (positions[&e.source()], positions[&e.target()]);
+        // columns are the sources of the random walk
+        triplets.push((t, s, w));
+        if (!directed || symmetrize == Symmetrize::Add) && s != t {
+            triplets.push((s, t, w));
+        }
+    }
+
+    if directed && symmetrize == Symmetrize::Max {
+        let mut weights: HashMap<(usize, usize), A> = HashMap::new();
+        for &(i, j, w) in triplets.iter() {
+            let sum = weights.entry((i, j)).or_insert_with(A::zero);
+            *sum = *sum + w;
+        }
+        // every pair of nodes is taken once, from the direction with the smaller row or the only direction with an edge
+        triplets = weights.iter()
+            .filter(|&(&(i, j), _)| i <= j || !weights.contains_key(&(j, i)))
+            .flat_map(|(&(i, j), &w)| {
+                let w = w.max(weights.get(&(j, i)).copied().unwrap_or_else(A::zero));
+                if i == j { vec![(i, j, w)] } else { vec![(i, j, w), (j, i, w)] }
+            })
+            .collect();
+    }
+
+    let n = ids.len();
+    let matrix = CscMatrix::from_triplets((n, n), &triplets)?;
+    Ok((ids, matrix))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use petgraph::graph::{DiGraph, NodeIndex, UnGraph};
+    use petgraph::graphmap::UnGraphMap;
+    use petgraph::stable_graph::StableUnGraph;
+
+    const EDGES: [(u32, u32, u32); 7] = [(0, 1, 1), (0, 2, 1), (1, 2, 1), (2, 3, 1), (3, 4, 1), (3, 5, 1), (4, 5, 1)];
+
+    fn sorted<Id: Ord + Copy>(clusters: &[HashSet<Id>]) -> Vec<Vec<Id>> {
+        let mut clusters: Vec<Vec<Id>> = clusters.iter()
+            .map(|c| {
+                let mut c: Vec<Id> = c.iter().copied().collect();
+                c.sort();
+                c
+            })
+            .collect();
+        clusters.sort();
+        clusters
+    }
+
+    #[test]
+    fn test_graph_map() {
+        let graph = UnGraphMap::<u32, u32>::from_edges(EDGES.iter());
+        let res = graph.mcl(&MclParams::<f64>::default()).unwrap();
+        assert_eq!(sorted(&res.clusters), vec![vec![0, 1, 2], vec![3, 4, 5]]);
+        assert_eq!(res.weight(4), Some(&4));
+    }
+
+    #[test]
+    fn test_stable_graph() {
+        // removing a node leaves a hole in the indices
+        let mut graph = StableUnGraph::<u32, u32>::from_edges(EDGES.iter());
+        let extra = graph.add_node(6);
+        graph.add_edge(extra, NodeIndex::new(5), 1);
+        graph.remove_node(NodeIndex::new(0));
+
+        let res = graph.mcl(&MclParams::<f64>::default()).unwrap();
+        let nodes = |v: &[usize]| v.iter().map(|&i| NodeIndex::new(i)).collect::<Vec<_>>();
+        assert_eq!(sorted(&res.clusters), vec![nodes(&[1, 2]), nodes(&[3, 4, 5, 6])]);
+        assert_eq!(res.weight(NodeIndex::new(0)), None);
+    }
+
+    #[test]
+    fn test_symmetrize() {
+        // 0->1 and 1->0 with different weights, a one-way edge 1->2 with a parallel edge and a self loop on 2
+        let graph = DiGraph::<(), f64>::from_edges([(0, 1, 1.), (1, 0, 3.), (1, 2, 1.), (1, 2, 1.), (2, 2, 1.)]);
+        let entries = |symmetrize| {
+            let matrix: CscMatrix<f64> = _adjacency(&graph, symmetrize).unwrap().1;
+            [(1, 0), (0, 1), (2, 1), (1, 2), (2, 2)].iter().map(|&(i, j)| matrix.get(i, j)).collect::<Vec<f64>>()
+        };
+
+        assert_eq!(entries(Symmetrize::None), vec![1., 3., 2., 0., 1.]);
+        assert_eq!(entries(Symmetrize::Add), vec![4., 4., 2., 2., 1.]);
+        assert_eq!(entries(Symmetrize::Max), vec![3., 3., 2., 2., 1.]);
+    }
+
+    #[test]
+    fn test_directed() {
+        let nodes = |v: &[usize]| v.iter().map(|&i| NodeIndex::new(i)).collect::<Vec<_>>();
+        let edges = [(0, 1, 1.), (1, 2, 1.), (2, 3, 1.), (3, 4, 1.), (4, 5, 1.)];
+        let params = MclParams::<f64>::default();
+
+        // the flow along a directed path ends in its last nodes, which attract the whole path
+        let directed = DiGraph::<(), f64>::from_edges(edges).mcl(&params).unwrap();
+        assert_eq!(sorted(&directed.clusters), vec![nodes(&[0, 1, 2, 3, 4, 5])]);
+
+        let undirected = UnGraph::<(), f64>::from_edges(edges).mcl(&params).unwrap();
+        assert_eq!(sorted(&undirected.clusters), vec![nodes(&[0, 1, 2]), nodes(&[3, 4, 5])]);
+
+        // once symmetrized, the directed path is clustered as the undirected one
+        for &symmetrize in [Symmetrize::Add, Symmetrize::Max].iter() {
+            let symmetrized = DiGraph::<(), f64>::from_edges(edges).mcl_symmetrized(&params, symmetrize).unwrap();
+            assert_eq!(sorted(&symmetrized.clusters), sorted(&undirected.clusters));
+        }
+        // symmetrizing an undirected graph changes nothing
+        let same = UnGraph::<(), f64>::from_edges(edges).mcl_symmetrized(&params, Symmetrize::Add).unwrap();
+        assert_eq!(sorted(&same.clusters), sorted(&undirected.clusters));
+
+        let negative = DiGraph::<(), f64>::from_edges([(0, 1, -1.)]);
+        assert!(negative.mcl(&params).is_err());
+    }
+
+    #[test]
+    fn test_self_loop() {
+        // a heavy self loop on the bridge node 2
+        let mut graph = UnGraph::<u32, u32>::from_edges(EDGES.iter());
+        graph.add_edge(NodeIndex::new(2), NodeIndex::new(2), 100);
+
+        // the self loop is replaced by the loop value
+        let res = graph.mcl(&MclParams::<f64>::default()).unwrap();
+        let plain = UnGraph::<u32, u32>::from_edges(EDGES.iter()).mcl(&MclParams::<f64>::default()).unwrap();
+        assert_eq!(sorted(&res.clusters), sorted(&plain.clusters));
+
+        // without a loop value the self loop is kept, and node 2 attracts both triangles
+        let params = MclParams::<f64>::builder().loop_value(0.).build().unwrap();
+        let res = graph.mcl(&params).unwrap();
+        let nodes = |v: &[usize]| v.iter().map(|&i| NodeIndex::new(i)).collect::<Vec<_>>();
+        assert_eq!(sorted(&res.clusters), vec![nodes(&[0, 1, 2, 3, 4, 5])]);
+    }
+}
@@ -0,0 +1,218 @@
+/*!
+Diagnostics of a clustering on the matrix given to MCL, in the manner of `clm info` of the reference mcl.
+
+[`cluster_info`] measures how well the clusters capture the edge weights of every node:
+
+- the efficiency of a node compares the distribution of its edge weights with the uniform distribution over the other nodes of its
+  cluster. For the weights `c` of a column normalized to sum 1, and `k` other nodes in the cluster holding the mass `m` of `c`,
+  it is `m^2 / (k Σ c_i^2)`, which is 1 only if all the weight goes to the other nodes of the cluster in equal parts
+- the efficiency of the clustering is the mean efficiency of the nodes
+- the mass fraction is the fraction of the edge weight between nodes of the same cluster
+- the area fraction is the fraction of the ordered pairs of distinct nodes that are in the same cluster
+
+Column `j` holds the weights of the edges leaving node `j`, as in [`MclExt::mcl`](super::MclExt::mcl), and the diagonal is ignored
+because mcl adds its own self loops. Nodes outside every cluster count as singletons, as mcl puts every node in a cluster,
+and a node in several clusters takes its efficiency in the best of them.
+
+```rust
+# #[macro_use] extern crate ndarray;
+use graph_clustering_rs::mcl::*;
+use ndarray::Array2;
+
+// two triangles joined by the edge 2-3
+let input: Array2<f64> = array![[0., 1., 1., 0., 0., 0.],
+                                [1., 0., 1., 0., 0., 0.],
+                                [1., 1., 0., 1., 0., 0.],
+                                [0., 0., 1., 0., 1., 1.],
+                                [0., 0., 0., 1., 0., 1.],
+                                [0., 0., 0., 1., 1., 0.]];
+let clustering = get_clusters(&input.mcl(&MclParams::default()).unwrap()).unwrap();
+let info = cluster_info(&input, &clustering).unwrap();
+
+assert_eq!(info.sizes.count, 2);
+assert_eq!(info.mass_fraction, 6. / 7.);
+// the nodes of the bridge send a third of their weight outside
+assert_eq!(info.node_efficiency[0], 1.);
+assert!((info.node_efficiency[2] - 2. / 3.).abs() < 1e-12);
+```
+*/
+
+use anyhow::{ensure, Result};
+use ndarray::{ArrayBase, Axis, Data, Ix2};
+use num_traits::{Float, zero, one};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::clustering::Clustering;
+
+/// Efficiency and coverage of a clustering on an MCL input matrix
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ClusterInfo<A> {
+    /// Mean efficiency of the nodes
+    pub efficiency: A,
+    /// Efficiency of every node, 1 for a node without edges in a singleton and 0 for one in a larger cluster
+    pub node_efficiency: Vec<A>,
+    /// Fraction of the edge weight inside the clusters, 0 without edges
+    pub mass_fraction: A,
+    /// Fraction of the ordered pairs of distinct nodes inside the clusters
+    pub area_fraction: A,
+    /// Number of nodes in singletons
+    pub singletons: usize,
+    /// Distribution of the cluster sizes
+    pub sizes: SizeDistribution<A>,
+}
+
+/// Distribution of the cluster sizes, singletons included
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SizeDistribution<A> {
+    /// Number of clusters
+    pub count: usize,
+    /// Size of the smallest cluster
+    pub min: usize,
+    /// Size of the largest cluster
+    pub max: usize,
+    /// Median size, the upper one of the two middle sizes for an even number of clusters
+    pub median: usize,
+    /// Mean size of the clusters
+    pub mean: A,
+    /// Mean size of the cluster of a node, `Σ size^2 / n`
+    pub center: A,
+}
+
+/// Efficiency, mass fraction and cluster sizes of `clustering` on the square matrix of edge weights given to MCL
+pub fn cluster_info<A, S>(matrix: &ArrayBase<S, Ix2>, clustering: &Clustering) -> Result<ClusterInfo<A>>
+where
+    A: Float,
+    S: Data<Elem = A>
+{
+    let (n, m) = matrix.dim();
+    ensure!(n == m, "the matrix must be square, found {}x{}", n, m);
+    ensure!(clustering.node_count() <= n, "the clustering has {} nodes, but the matrix has {}", clustering.node_count(), n);
+    ensure!(matrix.iter().all(|&w| w.is_finite() && w >= zero()), "edge weights must be finite and not negative");
+
+    let mut node_efficiency = Vec::with_capacity(n);
+    let mut inner: A = zero();
+    let mut total: A = zero();
+
+    for (j, column) in matrix.axis_iter(Axis(1)).enumerate() {
+        let mass: A = column.iter().enumerate().filter(|&(i, _)| i != j).map(|(_, &w)| w).fold(zero(), |acc, w| acc + w);
+        total = total + mass;
+        inner = inner + column.iter().enumerate()
+            .filter(|&(i, _)| i != j && clustering.share_cluster(i, j))
+            .map(|(_, &w)| w)
+            .fold(zero(), |acc, w| acc + w);
+
+        let clusters = clustering.clusters_of(j);
+        if mass == zero() {
+            let singleton = clusters.iter().all(|&c| clustering[c].len() == 1);
+            node_efficiency.push(if singleton { one() } else { zero() });
+            continue;
+        }
+
+        let center = column.iter().enumerate()
+            .filter(|&(i, _)| i != j)
+            .map(|(_, &w)| (w / mass).powi(2))
+            .fold(zero(), |acc: A, p| acc + p);
+        let efficiency = clusters.iter()
+            .map(|&c| {
+                let others = clustering[c].iter().filter(|&&i| i != j);
+                let k = A::from(clustering[c].len() - 1).unwrap();
+                let captured = others.map(|&i| matrix[[i, j]] / mass).fold(zero(), |acc: A, p| acc + p);
+                if k > zero() { captured * captured / (k * center) } else { zero() }
+            })
+            .fold(zero(), |acc: A, e| acc.max(e));
+        node_efficiency.push(efficiency);
+    }
+
+    // nodes outside every cluster are singletons
+    let mut sizes = clustering.sizes();
+    sizes.extend(clustering.unassigned().iter().map(|_| 1));
+    sizes.extend((clustering.node_count()..n).map(|_| 1));
+    sizes.sort_unstable();
+
+    let count = sizes.len();
+    let a = |x: usize| A::from(x).unwrap();
+    let efficiency = if n > 0 { node_efficiency.iter().fold(zero(), |acc: A, &e| acc + e) / a(n) } else { zero() };
+    let mass_fraction = if total > zero() { inner / total } else { zero() };
+    let area: usize = sizes.iter().map(|&s| s * s.saturating_sub(1)).sum();
+    let area_fraction = if n > 1 { a(area) / a(n * (n - 1)) } else { zero() };
+
+    let distribution = SizeDistribution {
+        count,
+        min: sizes.first().copied().unwrap_or(0),
+        max: sizes.last().copied().unwrap_or(0),
+        median: if count > 0 { sizes[count / 2] } else { 0 },
+        mean: if count > 0 { a(sizes.iter().sum()) / a(count) } else { zero() },
+        center: if n > 0 { a(sizes.iter().map(|&s| s * s).sum()) / a(n) } else { zero() },
+    };
+
+    Ok(ClusterInfo {
+        efficiency,
+        node_efficiency,
+        mass_fraction,
+        area_fraction,
+        singletons: sizes.iter().filter(|&&s| s == 1).count(),
+        sizes: distribution,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use ndarray::Array2;
+
+    fn two_triangles() -> Array2<f64> {
+        let mut matrix = Array2::zeros((6, 6));
+        for &(a, b) in [(0, 1), (0, 2), (1, 2), (2, 3), (3, 4), (3, 5), (4, 5)].iter() {
+            matrix[[a, b]] = 1.;
+            matrix[[b, a]] = 1.;
+        }
+        matrix
+    }
+
+    #[test]
+    fn test_cluster_info() {
+        let matrix = two_triangles();
+        let info = cluster_info(&matrix, &Clustering::from_membership(&[0, 0, 0, 1, 1, 1])).unwrap();
+
+        assert_abs_diff_eq!(info.efficiency, 8. / 9., epsilon = 1e-12);
+        assert_abs_diff_eq!(info.mass_fraction, 6. / 7., epsilon = 1e-12);
+        assert_abs_diff_eq!(info.area_fraction, 0.4, epsilon = 1e-12);
+        assert_eq!(info.singletons, 0);
+        assert_eq!(info.sizes, SizeDistribution { count: 2, min: 3, max: 3, median: 3, mean: 3., center: 3. });
+
+        // self loops are ignored
+        let mut with_loops = matrix.clone();
+        with_loops.diag_mut().iter_mut().for_each(|d| *d = 5.);
+        let same = cluster_info(&with_loops, &Clustering::from_membership(&[0, 0, 0, 1, 1, 1])).unwrap();
+        assert_eq!(same, info);
+    }
+
+    #[test]
+    fn test_singletons() {
+        // node 6 has no edges
+        let mut matrix = Array2::zeros((7, 7));
+        matrix.slice_mut(s![..6, ..6]).assign(&two_triangles());
+
+        // node 5 is unassigned and node 6 is left out of the clustering
+        let clustering = Clustering::new(6, vec![vec![0, 1, 2], vec![3, 4]]).unwrap();
+        let info = cluster_info(&matrix, &clustering).unwrap();
+
+        assert_abs_diff_eq!(info.node_efficiency[3], 1. / 3., epsilon = 1e-12);
+        assert_eq!(info.node_efficiency[5], 0.);
+        assert_eq!(info.node_efficiency[6], 1.);
+        assert_abs_diff_eq!(info.mass_fraction, 8. / 14., epsilon = 1e-12);
+        assert_eq!(info.singletons, 2);
+        assert_eq!(info.sizes.count, 4);
+        assert_eq!((info.sizes.min, info.sizes.max, info.sizes.median), (1, 3, 2));
+        assert_abs_diff_eq!(info.sizes.center, 15. / 7., epsilon = 1e-12);
+
+        assert!(cluster_info(&Array2::<f64>::zeros((2, 3)), &clustering).is_err());
+        assert!(cluster_info(&Array2::<f64>::zeros((3, 3)), &clustering).is_err());
+        assert!(cluster_info(&Array2::from_elem((6, 6), -1.), &clustering).is_err());
+    }
+}
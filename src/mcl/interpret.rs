@@ -0,0 +1,166 @@
+/*!
+Interpretation of the MCL limit matrix as clusters, following the reference mcl.
+
+Nodes with a nonzero diagonal entry are attractors. Attractors that attract one another form
+an attractor system, and the cluster of a system is the set of nodes attracted by any of its
+attractors. A node attracted by several systems is an overlap, which is handled with an
+[`OverlapPolicy`].
+
+```rust
+# #[macro_use] extern crate ndarray;
+use graph_clustering_rs::mcl::*;
+use ndarray::Array2;
+
+// node 2 is attracted by the attractors 0 and 3, more strongly by 3
+let limit: Array2<f64> = array![[1., 1., 0.3, 0., 0.],
+                                [0., 0., 0.,  0., 0.],
+                                [0., 0., 0.,  0., 0.],
+                                [0., 0., 0.7, 1., 1.],
+                                [0., 0., 0.,  0., 0.]];
+
+let keep = interpret_clusters(&limit, OverlapPolicy::Keep).unwrap();
+assert_eq!(keep.clusters, vec![vec![0, 1, 2], vec![2, 3, 4]]);
+assert_eq!(keep.overlaps, vec![Overlap { node: 2, attractors: vec![0, 3] }]);
+
+let split = interpret_clusters(&limit, OverlapPolicy::Split).unwrap();
+assert_eq!(split.clusters, vec![vec![0, 1], vec![2, 3, 4]]);
+
+let drop = interpret_clusters(&limit, OverlapPolicy::Drop).unwrap();
+assert_eq!(drop.clusters, vec![vec![0, 1], vec![3, 4]]);
+```
+*/
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::Result;
+use num_traits::Float;
+
+use super::NonzeroExt;
+use crate::union_find::UnionFind;
+
+/// How nodes attracted by more than one attractor system are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverlapPolicy {
+    /// Keep the node in every cluster that attracts it
+    #[default]
+    Keep,
+    /// Put the node only into the cluster whose attractor attracts it the most
+    Split,
+    /// Remove the node from all clusters
+    Drop,
+}
+
+/// Node attracted by several attractor systems
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlap {
+    /// The overlapping node
+    pub node: usize,
+    /// The attractor systems attracting the node, each represented by its smallest attractor
+    pub attractors: Vec<usize>,
+}
+
+/// Clusters read from the MCL limit matrix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interpretation {
+    /// Sorted clusters, each with sorted members
+    pub clusters: Vec<Vec<usize>>,
+    /// Overlapping nodes in ascending order, reported whatever the policy
+    pub overlaps: Vec<Overlap>,
+}
+
+/// Read the clusters from the MCL limit matrix, joining attractor systems and resolving overlaps with `policy`
+pub fn interpret_clusters<A, M>(matrix: &M, policy: OverlapPolicy) -> Result<Interpretation>
+where
+    A: Float,
+    M: NonzeroExt<A>,
+{
+    let entries = matrix.nonzero_entries();
+
+    let attractors: HashSet<usize> = entries.iter()
+        .filter(|(i, j, _)| i == j)
+        .map(|&(i, _, _)| i)
+        .collect();
+
+    // attractors attracting one another belong to the same system, represented by its smallest attractor
+    let mut systems = UnionFind::new(matrix.node_count());
+    for &(i, j, _) in entries.iter() {
+        if i != j && attractors.contains(&i) && attractors.contains(&j) {
+            systems.union(i, j);
+        }
+    }
+
+    // node -> system representative -> strongest attraction
+    let mut attraction: BTreeMap<usize, BTreeMap<usize, A>> = BTreeMap::new();
+    for &(i, j, x) in entries.iter() {
+        if attractors.contains(&i) {
+            let system = systems.find(i);
+            let strength = attraction.entry(j).or_default().entry(system).or_insert(x);
+            if x.abs() > strength.abs() {
+                *strength = x;
+            }
+        }
+    }
+
+    let mut clusters: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    let mut overlaps: Vec<Overlap> = Vec::new();
+
+    for (node, systems) in attraction.into_iter() {
+        if systems.len() > 1 {
+            overlaps.push(Overlap { node, attractors: systems.keys().copied().collect() });
+        }
+
+        let chosen: Vec<usize> = match policy {
+            _ if systems.len() == 1 => systems.keys().copied().collect(),
+            OverlapPolicy::Keep => systems.keys().copied().collect(),
+            OverlapPolicy::Split => {
+                // iteration is in ascending order, so ties go to the smallest attractor
+                let mut best: Option<(usize, A)> = None;
+                for (&system, &x) in systems.iter() {
+                    match best {
+                        Some((_, b)) if x.abs() <= b.abs() => {},
+                        _ => best = Some((system, x)),
+                    }
+                }
+                best.into_iter().map(|(system, _)| system).collect()
+            },
+            OverlapPolicy::Drop => Vec::new(),
+        };
+
+        for system in chosen {
+            clusters.entry(system).or_default().push(node);
+        }
+    }
+
+    let mut clusters: Vec<Vec<usize>> = clusters.into_values().collect();
+    clusters.sort();
+
+    Ok(Interpretation { clusters, overlaps })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ndarray::Array2;
+
+    #[test]
+    fn test_join_attractor_systems() {
+        // attractors 0 and 1 attract each other
+        let limit: Array2<f64> = array![[0.5, 0.5, 0., 0.],
+                                        [0.5, 0.5, 1., 0.],
+                                        [0.,  0.,  0., 0.],
+                                        [0.,  0.,  0., 1.]];
+        let res = interpret_clusters(&limit, OverlapPolicy::Keep).unwrap();
+        assert_eq!(res.clusters, vec![vec![0, 1, 2], vec![3]]);
+        assert!(res.overlaps.is_empty());
+    }
+
+    #[test]
+    fn test_split_ties() {
+        let limit: Array2<f64> = array![[1., 0.5, 0.],
+                                        [0., 0.,  0.],
+                                        [0., 0.5, 1.]];
+        let res = interpret_clusters(&limit, OverlapPolicy::Split).unwrap();
+        assert_eq!(res.clusters, vec![vec![0, 1], vec![2]]);
+        assert_eq!(res.overlaps, vec![Overlap { node: 1, attractors: vec![0, 2] }]);
+    }
+}
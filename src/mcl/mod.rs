@@ -10,13 +10,16 @@ use graph_clustering_rs::mcl::*;
 use ndarray::Array2;
 
 // set parameters
-let expantion = 2;
-let inflation = 2.;
-let loop_value = 1.;
-let iterations = 100;
-let pruning_threshold = 0.0001;
-let pruning_frequency = 1;
-let convergence_check_frequency = 1;
+let params = MclParams::builder()
+    .expansion(2.)
+    .inflation(2.)
+    .loop_value(1.)
+    .iterations(100)
+    .pruning(PruneOptions::new(0.0001))
+    .pruning_frequency(1)
+    .convergence_check_frequency(1)
+    .build()
+    .unwrap();
 
 let input: Array2<f64> = array![[1., 1., 1., 0., 0., 0., 0.],
                                 [1., 1., 1., 0., 0., 0., 0.],
@@ -32,20 +35,51 @@ let output: Array2<f64> = array![[0., 0., 0., 0., 0., 0., 0.],
                                 [0., 0., 0., 0.5, 0.5, 0.5, 0.5],
                                 [0., 0., 0., 0., 0., 0., 0.],
                                 [0., 0., 0., 0.5, 0.5, 0.5, 0.5]];
-assert_abs_diff_eq!(input.mcl(expantion, inflation, loop_value, iterations, pruning_threshold, pruning_frequency, convergence_check_frequency).unwrap(), output)
+assert_abs_diff_eq!(input.mcl(&params).unwrap(), output)
 ```
+
+Petgraph graphs can be clustered directly with [`MclGraphExt`], see [`graph`].
+The efficiency and mass fraction of the clusters, as `clm info` reports them, are computed by [`cluster_info`], see [`info`].
+
+# Parallelism
+
+Expansion, inflation, normalization and pruning are computed column by column on the [rayon](https://docs.rs/rayon) global thread pool.
+Each column is always computed by a single thread in the same order, so the result does not depend on the number of threads.
+Use `RAYON_NUM_THREADS` or [`rayon::ThreadPool::install`](https://docs.rs/rayon/1/rayon/struct.ThreadPool.html#method.install) to control the number of threads.
 */
 
 use std::iter::Sum;
-use std::collections::HashSet;
-use anyhow::Result;
+use std::time::Instant;
+use anyhow::{ensure, Context, Result};
 
 use ndarray::{Array2, ArrayBase, Axis, Data, Dimension};
+use ndarray::parallel::prelude::*;
 
 use approx::{AbsDiffEq};
 
 use num_traits::{Float, zero, one};
 
+use crate::clustering::Clustering;
+use crate::observer::{Flow, Observer, Progress};
+
+pub mod graph;
+pub mod info;
+pub mod interpret;
+pub mod params;
+pub mod prune;
+pub mod report;
+pub mod sparse;
+pub use graph::{MclGraphClusters, MclGraphExt, Symmetrize};
+pub use info::{cluster_info, ClusterInfo, SizeDistribution};
+pub use interpret::{interpret_clusters, Interpretation, Overlap, OverlapPolicy};
+pub use params::{MclParams, MclParamsBuilder};
+pub use prune::PruneOptions;
+pub use report::{MclReport, MclState};
+pub use sparse::CscMatrix;
+
+use prune::prune_column;
+use report::column_chaos;
+
 pub trait PartiqlArgMaxExt<A, S, D>
 where
     S: Data<Elem = A>,
@@ -75,7 +109,7 @@ where
     }
 }
 
-pub trait MclExt<A>
+pub trait MclExt<A>: Sized + Clone
 where 
     A: Float,
 {   
@@ -94,7 +128,7 @@ where
     ///                                 [0., 0., 0.5]];
     /// assert_abs_diff_eq!(input.normalize().unwrap(), output)
     /// ```
-    fn normalize(&self) -> Result<Array2<A>>;
+    fn normalize(&self) -> Result<Self>;
 
     /// Apply cluster expansion to the given matrix with given power
     ///
@@ -111,7 +145,73 @@ where
     ///                 [0., 0., 0.25]];
     /// assert_abs_diff_eq!(input.expand(2).unwrap(), output) 
     /// ```
-    fn expand(&self, power: i32) -> Result<Array2<A>>;
+    fn expand(&self, power: i32) -> Result<Self> {
+        ensure!(power >= 1, "expansion power must be at least 1, got {}", power);
+
+        // exponentiation by squaring
+        let mut base: Self = self.clone();
+        let mut mat: Option<Self> = None;
+        let mut power = power;
+
+        loop {
+            if power & 1 == 1 {
+                mat = Some(match mat {
+                    Some(m) => m.matmul(&base)?,
+                    None => base.clone(),
+                });
+            }
+
+            power >>= 1;
+            if power == 0 { break; }
+            base = base.matmul(&base)?;
+        }
+
+        Ok(mat.unwrap())
+    }
+
+    /// Apply cluster expansion with a non-integer power, pruning between the multiplications
+    ///
+    /// The matrix is multiplied by itself `floor(power) - 1` times and pruned after every product.
+    /// The fractional part `f` of the power interpolates linearly between `M^k` and `M^(k+1)`.
+    /// Pruning removes mass from the columns without renormalizing them, which is left to the inflation that follows.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate ndarray;
+    /// # #[macro_use] extern crate approx;
+    /// use graph_clustering_rs::mcl::*;
+    /// use ndarray::Array2;
+    /// let input: Array2<f64> = array![[1., 0.5, 0.],
+    ///                                 [0., 0.5, 0.5],
+    ///                                 [0., 0., 0.5]];
+    /// let output: Array2<f64> = array![[1., 0.625, 0.125],
+    ///                                  [0., 0.375, 0.5  ],
+    ///                                  [0., 0.,    0.375]];
+    /// assert_abs_diff_eq!(input.expand_pruned(1.5, &PruneOptions::new(0.)).unwrap(), output)
+    /// ```
+    fn expand_pruned(&self, power: A, options: &PruneOptions<A>) -> Result<Self> {
+        ensure!(power.is_finite() && power >= one(), "expansion power must be finite and at least 1");
+
+        let k = power.floor().to_usize().context("expansion power is too large")?;
+        let frac = power - power.floor();
+
+        let mut mat: Self = self.clone();
+        for _ in 1..k {
+            mat = mat.matmul(self)?.prune_with(options)?;
+        }
+
+        if frac > zero() {
+            let next = mat.matmul(self)?.prune_with(options)?;
+            mat = mat.interpolate(&next, frac)?;
+        }
+
+        Ok(mat)
+    }
+
+    /// Matrix product of `self` and `other`
+    fn matmul(&self, other: &Self) -> Result<Self>;
+
+    /// Linear interpolation `(1 - t) * self + t * other`
+    fn interpolate(&self, other: &Self, t: A) -> Result<Self>;
 
     ///  Apply cluster inflation to the given matrix with given power
     /// 
@@ -126,7 +226,7 @@ where
     ///                                  [0.8, 0.8]];
     /// assert_abs_diff_eq!(input.inflate(2.).unwrap(), output)
     /// ```
-    fn inflate(&self, power: A) -> Result<Array2<A>>;
+    fn inflate(&self, power: A) -> Result<Self>;
 
     /// prune the matrix below threshold
     /// The maximum value in each col is not pruned
@@ -141,12 +241,29 @@ where
     /// let output: Array2<f64> = array![[0., 2., 3.,], [3., 0., 4.]];
     /// assert_abs_diff_eq!(input.prune(threshold).unwrap(), output)
     /// ``` 
-    fn prune(&self, threshold: A) -> Result<Array2<A>>;
+    fn prune(&self, threshold: A) -> Result<Self> {
+        self.prune_with(&PruneOptions::new(threshold))
+    }
 
-    /// Add self loop to the matrix
+    /// prune the matrix column by column with the mcl resource scheme (see [`PruneOptions`])
+    fn prune_with(&self, options: &PruneOptions<A>) -> Result<Self>;
+
+    /// Replace the diagonal of the square matrix with `loop_value`, dropping the self loops it had
     fn add_self_loop(&mut self, loop_value: A) -> Result<()>;
 
-    /// mcl clustering from ndarray::Array2
+    /// Check whether the matrix is unchanged from the previous iteration
+    fn converged(&self, other: &Self) -> bool;
+
+    /// Chaos of the matrix, the maximum chaos of its columns (see [`MclReport::chaos`])
+    fn chaos(&self) -> A;
+
+    /// Frobenius norm of `self - other`
+    fn change_norm(&self, other: &Self) -> A;
+
+    /// Number of nonzero entries
+    fn nnz(&self) -> usize;
+
+    /// mcl clustering with the given parameters
     ///
     /// ```
     /// # #[macro_use] extern crate ndarray;
@@ -154,13 +271,16 @@ where
     /// use graph_clustering_rs::mcl::*;
     /// use ndarray::Array2;
     ///
-    /// let expansion = 2;
-    /// let inflation = 2.;
-    /// let loop_value = 1.;
-    /// let iterations = 100;
-    /// let pruning_threshold = 0.0001;
-    /// let pruning_frequency = 1;
-    /// let convergence_check_frequency = 1;
+    /// let params = MclParams::builder()
+    ///     .expansion(2.)
+    ///     .inflation(2.)
+    ///     .loop_value(1.)
+    ///     .iterations(100)
+    ///     .pruning(PruneOptions::new(0.0001))
+    ///     .pruning_frequency(1)
+    ///     .convergence_check_frequency(1)
+    ///     .build()
+    ///     .unwrap();
     /// let input: Array2<f64> = array![[1., 1., 1., 0., 0., 0., 0.],
     ///                                 [1., 1., 1., 0., 0., 0., 0.],
     ///                                 [1., 1., 1., 1., 0., 0., 0.],
@@ -175,18 +295,106 @@ where
     ///                                 [0., 0., 0., 0.5, 0.5, 0.5, 0.5],
     ///                                 [0., 0., 0., 0., 0., 0., 0.],
     ///                                 [0., 0., 0., 0.5, 0.5, 0.5, 0.5]];
-    /// assert_abs_diff_eq!(input.mcl(expansion, inflation, loop_value, iterations, pruning_threshold, pruning_frequency, convergence_check_frequency).unwrap(), output)
+    /// assert_abs_diff_eq!(input.mcl(&params).unwrap(), output)
+    /// ```
+    ///
+    fn mcl(&self, params: &MclParams<A>) -> Result<Self> {
+        self.mcl_with_report(params).map(|(mat, _)| mat)
+    }
+
+    /// mcl clustering that also returns the diagnostics of the run
+    ///
     /// ```
+    /// # #[macro_use] extern crate ndarray;
+    /// use graph_clustering_rs::mcl::*;
+    /// use ndarray::Array2;
     ///
-    fn mcl(&self,
-        expansion: i32,
-        inflation: A,
-        loop_value: A,
-        iterations: usize,
-        pruning_threshold: A,
-        pruning_frequency: usize,
-        convergence_check_frequency: usize,
-    ) -> Result<Array2<A>>;
+    /// let input: Array2<f64> = array![[1., 1., 1., 0., 0., 0., 0.],
+    ///                                 [1., 1., 1., 0., 0., 0., 0.],
+    ///                                 [1., 1., 1., 1., 0., 0., 0.],
+    ///                                 [0., 0., 1., 1., 1., 0., 1.],
+    ///                                 [0., 0., 0., 1., 1., 1., 1.],
+    ///                                 [0., 0., 0., 0., 1., 1., 1.],
+    ///                                 [0., 0., 0., 1., 1., 1., 1.]];
+    /// let params = MclParams::builder().iterations(3).build().unwrap();
+    /// let (_, report) = input.mcl_with_report(&params).unwrap();
+    /// assert!(!report.converged);
+    /// assert_eq!(report.iterations, 3);
+    /// ```
+    fn mcl_with_report(&self, params: &MclParams<A>) -> Result<(Self, MclReport<A>)> {
+        self.mcl_observed(params, &mut |_: &Progress<MclState<A>>| Flow::Continue)
+    }
+
+    /// mcl clustering that calls `observer` after every iteration, the converged one included
+    ///
+    /// If the observer returns [`Flow::Stop`], the current matrix is returned as a partial result
+    /// and the report is marked as interrupted and not converged.
+    /// See [`observer`](crate::observer) for an example.
+    fn mcl_observed<O>(&self, params: &MclParams<A>, observer: &mut O) -> Result<(Self, MclReport<A>)>
+    where
+        O: Observer<MclState<A>>,
+    {
+        params.validate()?;
+
+        let start = Instant::now();
+        let mut report = MclReport::new();
+
+        let mut mat: Self = self.clone();
+
+        if params.loop_value > zero() {
+            mat.add_self_loop(params.loop_value)?
+        }
+
+        mat = mat.normalize()?;
+
+        for i in 0..params.iterations {
+            let last_mat = mat.clone();
+
+            mat = if params.expansion.fract() == zero() {
+                mat.expand(params.expansion.to_i32().context("expansion is too large")?)?
+            } else {
+                mat.expand_pruned(params.expansion, &params.pruning)?
+            };
+            mat = mat.inflate(params.inflation)?;
+            
+            if i % params.pruning_frequency == params.pruning_frequency - 1 {
+                mat = mat.prune_with(&params.pruning)?;
+            }
+
+            let state = MclState {
+                chaos: mat.chaos(),
+                change_norm: mat.change_norm(&last_mat),
+                nnz: mat.nnz(),
+            };
+            report.iterations += 1;
+            report.chaos.push(state.chaos);
+            report.change_norms.push(state.change_norm);
+            report.nnz.push(state.nnz);
+
+            let converged = i % params.convergence_check_frequency == params.convergence_check_frequency - 1 && mat.converged(&last_mat);
+
+            // the observer also sees the last iteration, a stop request after convergence changes nothing
+            let progress = Progress {
+                iteration: i + 1,
+                total: Some(params.iterations),
+                elapsed: start.elapsed(),
+                state,
+            };
+            let flow = observer.observe(&progress);
+
+            if converged {
+                report.converged = true;
+                break;
+            }
+            if flow == Flow::Stop {
+                report.interrupted = true;
+                break;
+            }
+        }
+
+        report.elapsed = start.elapsed();
+        Ok((mat, report))
+    }
 }
 
 fn _handle_zeros_in_scale<A: Float>(scale: A) -> A {
@@ -195,110 +403,128 @@ fn _handle_zeros_in_scale<A: Float>(scale: A) -> A {
 
 impl<A> MclExt<A> for Array2<A>
 where
-    A: 'static + Float + Sum + AbsDiffEq,
+    A: 'static + Float + Sum + AbsDiffEq + Send + Sync,
 {
-    fn normalize(&self) -> Result<Array2<A>> {
-        let mut vec: Vec<A> = Vec::new();
-
-        for row in self.t().axis_iter(Axis(0)) {
-            let norm_l1: A = _handle_zeros_in_scale(row.iter().map(|x| x.abs()).sum());
+    fn normalize(&self) -> Result<Self> {
+        let mut mat: Array2<A> = self.to_owned();
 
-            for &x in row.iter() {
-                vec.push( x / norm_l1)
-            } 
-        }
+        mat.axis_iter_mut(Axis(1)).into_par_iter().for_each(|mut col| {
+            let norm_l1: A = _handle_zeros_in_scale(col.iter().map(|x| x.abs()).sum());
+            col.mapv_inplace(|x| x / norm_l1);
+        });
 
-        let shape = (self.shape()[1], self.shape()[0]);
-        let mat: Array2<A> = unsafe {
-            Array2::from_shape_vec_unchecked(shape, vec).reversed_axes()
-        };
         Ok(mat)
     }
 
-    fn expand(&self, power: i32) -> Result<Array2<A>> {
-        // TODO! implement numpy.linalg.matrix_power
-        // Use binary decomposition to reduce the number of matrix multiplications        
-        let mut mat: Array2<A> = self.to_owned();
+    fn matmul(&self, other: &Self) -> Result<Self> {
+        ensure!(self.ncols() == other.nrows(), "shape mismatch: {:?} x {:?}", self.shape(), other.shape());
 
-        for _ in 0..power-1 {
-            mat = mat.dot(self)
-        }
+        let mut mat: Array2<A> = Array2::zeros((self.nrows(), other.ncols()));
+        mat.axis_iter_mut(Axis(1))
+            .into_par_iter()
+            .zip(other.axis_iter(Axis(1)).into_par_iter())
+            .for_each(|(mut col, rhs)| col.assign(&self.dot(&rhs)));
 
         Ok(mat)
     }
 
-    fn inflate(&self, power: A) -> Result<Array2<A>> {
-        self.mapv(|x| x.powf(power)).normalize()
+    fn interpolate(&self, other: &Self, t: A) -> Result<Self> {
+        ensure!(self.shape() == other.shape(), "shape mismatch: {:?} and {:?}", self.shape(), other.shape());
+        Ok(self.mapv(|x| x * (one::<A>() - t)) + &other.mapv(|x| x * t))
+    }
+
+    fn inflate(&self, power: A) -> Result<Self> {
+        let mut mat: Array2<A> = self.to_owned();
+        mat.par_mapv_inplace(|x| x.powf(power));
+        mat.normalize()
     }
 
     fn add_self_loop(&mut self, loop_value: A) -> Result<()> {
-        let shape = self.shape();
-        assert_eq!(shape[0], shape[1]);
+        ensure!(self.is_square(), "self loops need a square matrix, got {:?}", self.shape());
 
-        for i in 0..shape[0] {
+        for i in 0..self.nrows() {
             self[(i, i)] = loop_value;
         }
 
         Ok(())
     }
 
-    fn prune(&self, threshold: A) -> Result<Array2<A>> {
-        let mat: Array2<A> = self.to_owned();
-        let mut pruned = mat.mapv(|x| if x < threshold { zero() } else {x});
-
-        for (i, row) in mat.axis_iter(Axis(1)).enumerate() {
-            let c = row.argmax()?;
-            pruned[(c, i)] = self[(c, i)];
-        }
+    fn prune_with(&self, options: &PruneOptions<A>) -> Result<Self> {
+        let mut pruned: Array2<A> = Array2::zeros(self.dim());
+
+        pruned.axis_iter_mut(Axis(1))
+            .into_par_iter()
+            .zip(self.axis_iter(Axis(1)).into_par_iter())
+            .for_each(|(mut pruned_col, col)| {
+                let (rows, values): (Vec<usize>, Vec<A>) = col.iter()
+                                                            .enumerate()
+                                                            .filter(|(_, &x)| x != zero())
+                                                            .unzip();
+                for (i, x) in prune_column(&rows, &values, options) {
+                    pruned_col[i] = x;
+                }
+            });
         
         Ok(pruned)
     }
 
-    fn mcl(&self, expansion: i32, inflation: A, loop_value: A, iterations: usize, pruning_threshold: A, pruning_frequency: usize, convergence_check_frequency: usize) -> Result<Array2<A>> {
-        let mut mat: Array2<A> = self.to_owned();
+    fn converged(&self, other: &Self) -> bool {
+        #[allow(deprecated)]
+        self.all_close(other, A::from(1e-8).unwrap())
+    }
 
-        if loop_value > zero() {
-            mat.add_self_loop(loop_value)?
-        }
+    fn chaos(&self) -> A {
+        self.axis_iter(Axis(1))
+            .into_par_iter()
+            .map(|col| column_chaos(col.iter().copied()))
+            .reduce(zero, |a, b| a.max(b))
+    }
 
-        mat = mat.normalize()?;
+    fn change_norm(&self, other: &Self) -> A {
+        (self - other).mapv(|x| x * x).sum().sqrt()
+    }
 
-        for i in 0..iterations {
-            let last_mat = mat.clone();
+    fn nnz(&self) -> usize {
+        self.iter().filter(|&&x| x != zero()).count()
+    }
+}
 
-            mat = mat.expand(expansion)?.inflate(inflation)?;
-            
-            if i % pruning_frequency == pruning_frequency - 1 {
-                mat = mat.prune(pruning_threshold)?;
-            }
+/// Access to the nonzero entries of a matrix, which is all [`get_clusters`] needs to read clusters back
+pub trait NonzeroExt<A> {
+    /// Return `(row, col, value)` of every nonzero entry
+    fn nonzero_entries(&self) -> Vec<(usize, usize, A)>;
 
-            if i % convergence_check_frequency == convergence_check_frequency - 1 {
-                
-                #[allow(deprecated)]
-                if mat.all_close(&last_mat, A::from(1e-8).unwrap()) {
-                    break;
-                }
-            }
-        }
-        
-        Ok(mat)
+    /// Number of columns, one for every node
+    fn node_count(&self) -> usize;
+}
+
+impl<A: Float> NonzeroExt<A> for Array2<A> {
+    fn node_count(&self) -> usize {
+        self.ncols()
+    }
+
+    fn nonzero_entries(&self) -> Vec<(usize, usize, A)> {
+        self.indexed_iter()
+            .filter(|(_, &x)| x != zero())
+            .map(|((i, j), &x)| (i, j, x))
+            .collect()
     }
 }
 
 /// Retrieve the clusters from the matrix
 ///
+/// Attractor systems are joined and overlapping nodes are kept in every cluster.
+/// Use [`interpret_clusters`] to choose another [`OverlapPolicy`] and to see the overlaps.
+///
 /// ```
 /// # #[macro_use] extern crate ndarray;
 /// use graph_clustering_rs::mcl::*;
 /// use ndarray::Array2;
 ///
-/// let expansion = 2;
-/// let inflation = 2.;
-/// let loop_value = 1.;
-/// let iterations = 100;
-/// let pruning_threshold = 0.0001;
-/// let pruning_frequency = 1;
-/// let convergence_check_frequency = 1;
+/// let params = MclParams::builder()
+///     .pruning(PruneOptions::new(0.0001))
+///     .build()
+///     .unwrap();
 /// let input: Array2<f64> = array![[1., 1., 1., 0., 0., 0., 0.],
 ///                                 [1., 1., 1., 0., 0., 0., 0.],
 ///                                 [1., 1., 1., 1., 0., 0., 0.],
@@ -306,32 +532,17 @@ where
 ///                                 [0., 0., 0., 1., 1., 1., 1.],
 ///                                 [0., 0., 0., 0., 1., 1., 1.],
 ///                                 [0., 0., 0., 1., 1., 1., 1.]];
-/// let output = input.mcl(expansion, inflation, loop_value, iterations, pruning_threshold, pruning_frequency, convergence_check_frequency).unwrap();
+/// let output = input.mcl(&params).unwrap();
 /// let target = vec![vec![0, 1, 2], vec![3, 4, 5, 6]];
 /// assert_eq!(get_clusters(&output).unwrap(), target);
 /// ```
-pub fn get_clusters<A: Float>(matrix: &Array2<A>) -> Result<Vec<Vec<usize>>> {
-    let mut res_set: HashSet<Vec<usize>> = HashSet::new();
-
-    let attractors: Vec<usize> = matrix.diag()
-                                                    .iter()
-                                                    .enumerate()
-                                                    .filter(|(_, &x)| x != zero())
-                                                    .map(|(i, _)| i)
-                                                    .collect();
-    for &attr in attractors.iter() {
-        let v: Vec<usize> = matrix.row(attr)
-                                    .iter()
-                                    .enumerate()
-                                    .filter(|(_, &x)| x != zero())
-                                    .map(|(i, _)| i)
-                                    .collect();
-        res_set.insert(v);
-    }
-
-    let mut res: Vec<Vec<usize>> = res_set.into_iter().collect();
-    res.sort();
-    Ok(res)
+pub fn get_clusters<A, M>(matrix: &M) -> Result<Clustering>
+where
+    A: Float,
+    M: NonzeroExt<A>,
+{
+    let res = interpret_clusters(matrix, OverlapPolicy::Keep)?;
+    Clustering::new(matrix.node_count(), res.clusters)
 }
 
 
@@ -400,6 +611,39 @@ mod test {
         assert_abs_diff_eq!(input.expand(2).unwrap(), output)    
     }
 
+    #[test]
+    fn test_expand_power() {
+        let input: Array2<f64> = array![[0.2, 0.5, 0.1],
+                                        [0.3, 0.25, 0.6],
+                                        [0.5, 0.25, 0.3]];
+        for power in 1..8 {
+            let mut naive = input.clone();
+            for _ in 1..power {
+                naive = naive.dot(&input);
+            }
+            assert_abs_diff_eq!(input.expand(power).unwrap(), naive, epsilon = 1e-12);
+        }
+        assert!(input.expand(0).is_err());
+    }
+
+    #[test]
+    fn test_expand_pruned() {
+        let input: Array2<f64> = array![[0.2, 0.5, 0.1],
+                                        [0.3, 0.25, 0.6],
+                                        [0.5, 0.25, 0.3]];
+        let no_pruning = PruneOptions::new(0.);
+        assert_abs_diff_eq!(input.expand_pruned(3., &no_pruning).unwrap(), input.expand(3).unwrap(), epsilon = 1e-12);
+
+        let half = input.expand_pruned(2.5, &no_pruning).unwrap();
+        let lerp = (input.expand(2).unwrap() + input.expand(3).unwrap()) / 2.;
+        assert_abs_diff_eq!(half, lerp, epsilon = 1e-12);
+        assert_abs_diff_eq!(half.sum_axis(Axis(0)), array![1., 1., 1.], epsilon = 1e-12);
+
+        assert!(input.expand_pruned(0.5, &no_pruning).is_err());
+        assert!(input.expand_pruned(f64::INFINITY, &no_pruning).is_err());
+        assert!(input.expand_pruned(f64::NAN, &no_pruning).is_err());
+    }
+
     #[test]
     fn test_inflate() {
         let input: Array2<f64> = array![[0.5, 0.5],
@@ -409,6 +653,17 @@ mod test {
         assert_abs_diff_eq!(input.inflate(2.).unwrap(), output)
     }
 
+    #[test]
+    fn test_add_self_loop() {
+        let mut input: Array2<f64> = array![[0., 1.], [1., 2.]];
+        input.add_self_loop(1.).unwrap();
+        assert_abs_diff_eq!(input, array![[1., 1.], [1., 1.]]);
+
+        let mut input: Array2<f64> = Array2::zeros((2, 3));
+        assert!(input.add_self_loop(1.).is_err());
+        assert!(input.mcl(&MclParams::default()).is_err());
+    }
+
     #[test]
     fn test_mcl() {
         let input: Array2<f64> = array![[1., 1., 1., 0., 0., 0., 0.],
@@ -425,9 +680,117 @@ mod test {
                                         [0., 0., 0., 0.5, 0.5, 0.5, 0.5],
                                         [0., 0., 0., 0., 0., 0., 0.],
                                         [0., 0., 0., 0.5, 0.5, 0.5, 0.5]];
-        assert_abs_diff_eq!(input.mcl(
-            2, 2., 1., 100, 0.001, 1, 1,
-        ).unwrap(), output)
+        let params = MclParams::builder()
+            .expansion(2.)
+            .inflation(2.)
+            .loop_value(1.)
+            .iterations(100)
+            .pruning(PruneOptions::new(0.001))
+            .pruning_frequency(1)
+            .convergence_check_frequency(1)
+            .build()
+            .unwrap();
+        assert_abs_diff_eq!(input.mcl(&params).unwrap(), output);
+
+        // valid, but too large to expand by squaring
+        let huge = MclParams { expansion: 3e9, ..params };
+        assert!(huge.validate().is_ok());
+        assert!(input.mcl(&huge).is_err());
+    }
+
+    #[test]
+    fn test_mcl_thread_count() {
+        let n = 60;
+        let input: Array2<f64> = Array2::from_shape_fn((n, n), |(i, j)| {
+            if i / 6 == j / 6 || (i + 1) % n == j { 1. } else { 0. }
+        });
+
+        let params = MclParams::builder().pruning(PruneOptions::new(0.001)).build().unwrap();
+        let run = |threads: usize| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| input.mcl(&params).unwrap())
+        };
+
+        let single = run(1);
+        assert_eq!(single, run(4));
+        assert_eq!(get_clusters(&single).unwrap().len(), 10);
+    }
+
+    #[test]
+    fn test_mcl_report() {
+        let input: Array2<f64> = array![[1., 1., 1., 0., 0., 0., 0.],
+                                        [1., 1., 1., 0., 0., 0., 0.],
+                                        [1., 1., 1., 1., 0., 0., 0.],
+                                        [0., 0., 1., 1., 1., 0., 1.],
+                                        [0., 0., 0., 1., 1., 1., 1.],
+                                        [0., 0., 0., 0., 1., 1., 1.],
+                                        [0., 0., 0., 1., 1., 1., 1.]];
+        let params = MclParams::builder().pruning(PruneOptions::new(0.001)).build().unwrap();
+        let (output, report) = input.mcl_with_report(&params).unwrap();
+
+        assert!(report.converged);
+        assert!(report.iterations < params.iterations);
+        assert_eq!(report.change_norms.len(), report.iterations);
+        assert_eq!(*report.nnz.last().unwrap(), output.nnz());
+        assert_abs_diff_eq!(*report.change_norms.last().unwrap(), 0., epsilon = 1e-8);
+        assert_abs_diff_eq!(*report.chaos.last().unwrap(), 0., epsilon = 1e-8);
+        assert!(report.chaos[0] > 0.);
+    }
+
+    #[test]
+    fn test_mcl_observed_stop() {
+        let input: Array2<f64> = array![[1., 1., 1., 0., 0., 0., 0.],
+                                        [1., 1., 1., 0., 0., 0., 0.],
+                                        [1., 1., 1., 1., 0., 0., 0.],
+                                        [0., 0., 1., 1., 1., 0., 1.],
+                                        [0., 0., 0., 1., 1., 1., 1.],
+                                        [0., 0., 0., 0., 1., 1., 1.],
+                                        [0., 0., 0., 1., 1., 1., 1.]];
+        let params = MclParams::builder().pruning(PruneOptions::new(0.001)).build().unwrap();
+
+        let mut seen = Vec::new();
+        let mut observer = |p: &Progress<MclState<f64>>| {
+            seen.push(p.iteration);
+            if p.state.chaos < 0.5 { Flow::Stop } else { Flow::Continue }
+        };
+        let (partial, report) = input.mcl_observed(&params, &mut observer).unwrap();
+
+        assert!(report.interrupted && !report.converged);
+        assert_eq!(seen, (1..=report.iterations).collect::<Vec<_>>());
+        assert!(*report.chaos.last().unwrap() < 0.5);
+        assert_eq!(partial.nnz(), *report.nnz.last().unwrap());
+    }
+
+    #[test]
+    fn test_mcl_observed_converged() {
+        let input: Array2<f64> = array![[1., 1., 1., 0., 0., 0., 0.],
+                                        [1., 1., 1., 0., 0., 0., 0.],
+                                        [1., 1., 1., 1., 0., 0., 0.],
+                                        [0., 0., 1., 1., 1., 0., 1.],
+                                        [0., 0., 0., 1., 1., 1., 1.],
+                                        [0., 0., 0., 0., 1., 1., 1.],
+                                        [0., 0., 0., 1., 1., 1., 1.]];
+        let params = MclParams::builder().pruning(PruneOptions::new(0.001)).build().unwrap();
+
+        let mut calls = 0;
+        let mut observer = |_: &Progress<MclState<f64>>| {
+            calls += 1;
+            Flow::Continue
+        };
+        let (_, report) = input.mcl_observed(&params, &mut observer).unwrap();
+
+        assert!(report.converged && !report.interrupted);
+        assert_eq!(calls, report.iterations);
+    }
+
+    #[test]
+    fn test_mcl_invalid_params() {
+        let input: Array2<f64> = array![[1., 1.], [1., 1.]];
+        let params: MclParams<f64> = MclParams { pruning_frequency: 0, ..Default::default() };
+        assert!(input.mcl(&params).is_err());
     }
 
     #[test]
@@ -442,4 +805,18 @@ mod test {
         let target = vec![vec![0, 1, 2], vec![3, 4, 5, 6]];
         assert_eq!(get_clusters(&input).unwrap(), target)
     }
+
+    #[test]
+    fn test_get_clusters_unassigned_last_node() {
+        // no attractor reaches the last column
+        let input: Array2<f64> = array![[1., 1., 0., 0.],
+                                        [0., 0., 0., 0.],
+                                        [0., 0., 1., 0.],
+                                        [0., 0., 0., 0.]];
+        let clustering = get_clusters(&input).unwrap();
+        assert_eq!(clustering, vec![vec![0, 1], vec![2]]);
+        assert_eq!(clustering.node_count(), 4);
+        assert_eq!(clustering.unassigned(), vec![3]);
+        assert_eq!(get_clusters(&CscMatrix::from_dense(&input)).unwrap(), clustering);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,191 @@
+/*!
+Parameters of [`MclExt::mcl`](super::MclExt::mcl).
+
+The defaults follow the reference mcl program: expansion 2, inflation 2.0, self loops of weight 1,
+and the `-P 4000 -S 500 -R 600 -pct 90` pruning scheme.
+
+```rust
+use graph_clustering_rs::mcl::*;
+
+let params: MclParams<f64> = MclParams::builder()
+    .inflation(1.4)
+    .iterations(50)
+    .build()
+    .unwrap();
+assert_eq!(params.expansion, 2.);
+
+// frequency 0 is rejected instead of panicking in the iteration loop
+assert!(MclParams::<f64>::builder().pruning_frequency(0).build().is_err());
+```
+*/
+
+use anyhow::{ensure, Result};
+use num_traits::{Float, one, zero};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::PruneOptions;
+
+/// Parameters of the MCL iteration
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, bound(deserialize = "A: Float + Deserialize<'de>")))]
+pub struct MclParams<A> {
+    /// Expansion power. Non-integer powers use [`MclExt::expand_pruned`](super::MclExt::expand_pruned)
+    pub expansion: A,
+    /// Inflation power
+    pub inflation: A,
+    /// Weight of the self loops added before the iteration, no loops are added if it is zero
+    pub loop_value: A,
+    /// Maximum number of iterations
+    pub iterations: usize,
+    /// Pruning options
+    pub pruning: PruneOptions<A>,
+    /// Prune every `pruning_frequency` iterations
+    pub pruning_frequency: usize,
+    /// Check the convergence every `convergence_check_frequency` iterations
+    pub convergence_check_frequency: usize,
+}
+
+impl<A: Float> Default for MclParams<A> {
+    fn default() -> Self {
+        let two = A::from(2.).unwrap();
+        Self {
+            expansion: two,
+            inflation: two,
+            loop_value: one(),
+            iterations: 100,
+            pruning: PruneOptions::new(A::from(1. / 4000.).unwrap())
+                .select(500)
+                .recover(600)
+                .pct(A::from(0.9).unwrap()),
+            pruning_frequency: 1,
+            convergence_check_frequency: 1,
+        }
+    }
+}
+
+impl<A: Float> MclParams<A> {
+    /// Start building parameters from the defaults
+    pub fn builder() -> MclParamsBuilder<A> {
+        MclParamsBuilder {
+            params: Self::default(),
+        }
+    }
+
+    /// Check that the parameters can be used to run MCL
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.expansion.is_finite() && self.expansion >= one(), "expansion must be at least 1");
+        ensure!(self.inflation.is_finite() && self.inflation > one(), "inflation must be greater than 1");
+        ensure!(self.loop_value.is_finite() && self.loop_value >= zero(), "loop_value must not be negative");
+        ensure!(self.pruning_frequency > 0, "pruning_frequency must be at least 1");
+        ensure!(self.convergence_check_frequency > 0, "convergence_check_frequency must be at least 1");
+
+        let pruning = &self.pruning;
+        ensure!(pruning.threshold >= zero(), "pruning threshold must not be negative");
+        if let Some(select) = pruning.select {
+            ensure!(select > 0, "pruning select must be at least 1");
+        }
+        if let Some(pct) = pruning.pct {
+            ensure!(pct >= zero() && pct <= one(), "pruning pct must be in [0, 1]");
+        }
+        if let Some(recover) = pruning.recover {
+            ensure!(pruning.pct.is_some(), "pruning recover needs pct to be set");
+            if let Some(select) = pruning.select {
+                ensure!(recover >= select, "pruning recover ({}) must not be smaller than select ({})", recover, select);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Builder of [`MclParams`]
+#[derive(Debug, Clone)]
+pub struct MclParamsBuilder<A> {
+    params: MclParams<A>,
+}
+
+impl<A: Float> MclParamsBuilder<A> {
+    /// Set the expansion power
+    pub fn expansion(mut self, expansion: A) -> Self {
+        self.params.expansion = expansion;
+        self
+    }
+
+    /// Set the inflation power
+    pub fn inflation(mut self, inflation: A) -> Self {
+        self.params.inflation = inflation;
+        self
+    }
+
+    /// Set the weight of the self loops added before the iteration
+    pub fn loop_value(mut self, loop_value: A) -> Self {
+        self.params.loop_value = loop_value;
+        self
+    }
+
+    /// Set the maximum number of iterations
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.params.iterations = iterations;
+        self
+    }
+
+    /// Set the pruning options
+    pub fn pruning(mut self, pruning: PruneOptions<A>) -> Self {
+        self.params.pruning = pruning;
+        self
+    }
+
+    /// Prune every `pruning_frequency` iterations
+    pub fn pruning_frequency(mut self, pruning_frequency: usize) -> Self {
+        self.params.pruning_frequency = pruning_frequency;
+        self
+    }
+
+    /// Check the convergence every `convergence_check_frequency` iterations
+    pub fn convergence_check_frequency(mut self, convergence_check_frequency: usize) -> Self {
+        self.params.convergence_check_frequency = convergence_check_frequency;
+        self
+    }
+
+    /// Validate and return the parameters
+    pub fn build(self) -> Result<MclParams<A>> {
+        self.params.validate()?;
+        Ok(self.params)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_is_valid() {
+        assert!(MclParams::<f64>::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        assert!(MclParams::<f64>::builder().convergence_check_frequency(0).build().is_err());
+        assert!(MclParams::<f64>::builder().inflation(1.).build().is_err());
+        assert!(MclParams::<f64>::builder().expansion(0.5).build().is_err());
+        assert!(MclParams::<f64>::builder().loop_value(-1.).build().is_err());
+        assert!(MclParams::<f64>::builder().pruning(PruneOptions::new(0.001).recover(10)).build().is_err());
+        assert!(MclParams::<f64>::builder().pruning(PruneOptions::new(0.001).select(10).recover(5).pct(0.9)).build().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let params: MclParams<f64> = MclParams::builder().inflation(1.4).build().unwrap();
+        let json = serde_json::to_string(&params).unwrap();
+        assert_eq!(serde_json::from_str::<MclParams<f64>>(&json).unwrap(), params);
+
+        let partial: MclParams<f64> = serde_json::from_str(r#"{"inflation": 3.0, "iterations": 20}"#).unwrap();
+        assert_eq!(partial.inflation, 3.);
+        assert_eq!(partial.iterations, 20);
+        assert_eq!(partial.expansion, 2.);
+    }
+}
@@ -0,0 +1,167 @@
+/*!
+Pruning in the style of the resource scheme of the reference mcl program.
+
+The reference mcl bounds the number of entries of each column after expansion with
+four knobs, which map onto [`PruneOptions`] as follows:
+
+| mcl option | `PruneOptions` | meaning |
+|------------|----------------|---------|
+| `-P`       | `threshold`    | entries below `threshold` (`1 / P` in mcl) are removed |
+| `-S`       | `select`       | at most `select` largest entries are kept per column |
+| `-R`       | `recover`      | recover up to `recover` entries if too much mass was pruned |
+| `-pct`     | `pct`          | fraction of the column mass that recovery aims for |
+
+The largest entry of each column is always kept.
+
+```rust
+# #[macro_use] extern crate ndarray;
+# #[macro_use] extern crate approx;
+use graph_clustering_rs::mcl::*;
+use ndarray::Array2;
+
+let input: Array2<f64> = array![[0.4, 0.1],
+                                [0.3, 0.1],
+                                [0.2, 0.1],
+                                [0.1, 0.7]];
+let options = PruneOptions::new(0.05).select(2);
+let output: Array2<f64> = array![[0.4, 0.1],
+                                 [0.3, 0. ],
+                                 [0.,  0. ],
+                                 [0.,  0.7]];
+assert_abs_diff_eq!(input.prune_with(&options).unwrap(), output);
+```
+*/
+
+use num_traits::{Float, zero};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Pruning options of MCL
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PruneOptions<A> {
+    /// Entries below this value are removed
+    pub threshold: A,
+    /// Maximum number of entries kept in each column
+    pub select: Option<usize>,
+    /// Maximum number of entries in a column after recovery
+    pub recover: Option<usize>,
+    /// Fraction of the column mass that recovery tries to restore, in `[0, 1]`
+    pub pct: Option<A>,
+}
+
+impl<A: Float> PruneOptions<A> {
+    /// Prune with an absolute threshold only, like [`MclExt::prune`](super::MclExt::prune)
+    pub fn new(threshold: A) -> Self {
+        Self {
+            threshold,
+            select: None,
+            recover: None,
+            pct: None,
+        }
+    }
+
+    /// Keep at most `n` entries per column (mcl `-S`)
+    pub fn select(mut self, n: usize) -> Self {
+        self.select = Some(n);
+        self
+    }
+
+    /// Recover up to `n` entries per column when the kept mass is below `pct` (mcl `-R`)
+    pub fn recover(mut self, n: usize) -> Self {
+        self.recover = Some(n);
+        self
+    }
+
+    /// Target fraction of the column mass used by recovery (mcl `-pct`)
+    pub fn pct(mut self, pct: A) -> Self {
+        self.pct = Some(pct);
+        self
+    }
+}
+
+/// Prune the nonzero entries of one column
+///
+/// Steps follow mcl: threshold, then selection of the largest entries, then recovery
+/// of the largest pruned entries while the kept mass is below `pct`.
+pub(crate) fn prune_column<A: Float>(rows: &[usize], values: &[A], options: &PruneOptions<A>) -> Vec<(usize, A)> {
+    if values.is_empty() {
+        return Vec::new();
+    }
+
+    // stable sort keeps the first maximum in row order on top, like `argmax`
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[b].partial_cmp(&values[a]).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut kept: Vec<bool> = values.iter().map(|&x| x >= options.threshold).collect();
+    kept[order[0]] = true;
+
+    if let Some(select) = options.select {
+        let mut n_kept = 0;
+        for &k in order.iter() {
+            if kept[k] {
+                if n_kept >= select.max(1) {
+                    kept[k] = false;
+                } else {
+                    n_kept += 1;
+                }
+            }
+        }
+    }
+
+    if let (Some(recover), Some(pct)) = (options.recover, options.pct) {
+        let total: A = values.iter().fold(zero(), |acc, &x| acc + x.abs());
+        let mut mass: A = values.iter().zip(kept.iter()).filter(|(_, &k)| k).fold(zero(), |acc, (&x, _)| acc + x.abs());
+        let mut n_kept = kept.iter().filter(|&&k| k).count();
+
+        for &k in order.iter() {
+            if n_kept >= recover || mass >= pct * total {
+                break;
+            }
+            if !kept[k] {
+                kept[k] = true;
+                mass = mass + values[k].abs();
+                n_kept += 1;
+            }
+        }
+    }
+
+    rows.iter()
+        .zip(values.iter())
+        .zip(kept.iter())
+        .filter(|(_, &k)| k)
+        .map(|((&i, &x), _)| (i, x))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_threshold_keeps_max() {
+        let pruned = prune_column(&[0, 1, 2], &[0.1, 0.2, 0.15], &PruneOptions::new(0.5));
+        assert_eq!(pruned, vec![(1, 0.2)]);
+    }
+
+    #[test]
+    fn test_select() {
+        let options = PruneOptions::new(0.).select(2);
+        let pruned = prune_column(&[0, 3, 5, 7], &[0.1, 0.4, 0.2, 0.3], &options);
+        assert_eq!(pruned, vec![(3, 0.4), (7, 0.3)]);
+    }
+
+    #[test]
+    fn test_recover() {
+        let values = [0.3, 0.25, 0.25, 0.2];
+        let options = PruneOptions::new(0.28).recover(3).pct(0.75);
+        let pruned = prune_column(&[0, 1, 2, 3], &values, &options);
+        assert_eq!(pruned, vec![(0, 0.3), (1, 0.25), (2, 0.25)]);
+
+        // recovery stops as soon as the target mass is reached
+        let options = PruneOptions::new(0.28).recover(4).pct(0.5);
+        let pruned = prune_column(&[0, 1, 2, 3], &values, &options);
+        assert_eq!(pruned, vec![(0, 0.3), (1, 0.25)]);
+    }
+}
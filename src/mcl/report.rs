@@ -0,0 +1,104 @@
+/*!
+Diagnostics of an MCL run returned by [`MclExt::mcl_with_report`](super::MclExt::mcl_with_report).
+
+```rust
+# #[macro_use] extern crate ndarray;
+use graph_clustering_rs::mcl::*;
+use ndarray::Array2;
+
+let input: Array2<f64> = array![[1., 1., 0., 0.],
+                                [1., 1., 0., 0.],
+                                [0., 0., 1., 1.],
+                                [0., 0., 1., 1.]];
+let (_, report) = input.mcl_with_report(&MclParams::default()).unwrap();
+assert!(report.converged);
+assert_eq!(report.chaos.len(), report.iterations);
+assert_eq!(*report.chaos.last().unwrap(), 0.);
+```
+*/
+
+use std::time::Duration;
+
+use num_traits::{Float, zero};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Report of an MCL run
+///
+/// The per-iteration vectors have one entry for every iteration that was run.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct MclReport<A> {
+    /// Number of iterations that were run
+    pub iterations: usize,
+    /// Whether the iteration stopped because the matrix converged
+    pub converged: bool,
+    /// Whether an [`Observer`](crate::observer::Observer) stopped the run before convergence
+    pub interrupted: bool,
+    /// Chaos of the matrix after each iteration, the convergence measure of the reference mcl
+    pub chaos: Vec<A>,
+    /// Frobenius norm of the change of the matrix in each iteration
+    pub change_norms: Vec<A>,
+    /// Number of nonzero entries after each iteration
+    pub nnz: Vec<usize>,
+    /// Wall time of the whole run
+    pub elapsed: Duration,
+}
+
+impl<A> MclReport<A> {
+    pub(crate) fn new() -> Self {
+        Self {
+            iterations: 0,
+            converged: false,
+            interrupted: false,
+            chaos: Vec::new(),
+            change_norms: Vec::new(),
+            nnz: Vec::new(),
+            elapsed: Duration::default(),
+        }
+    }
+}
+
+/// State of an MCL run after one iteration, passed to an [`Observer`](crate::observer::Observer)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MclState<A> {
+    /// Chaos of the matrix
+    pub chaos: A,
+    /// Frobenius norm of the change of the matrix in the iteration
+    pub change_norm: A,
+    /// Number of nonzero entries of the matrix
+    pub nnz: usize,
+}
+
+/// Chaos of one column, `(max / sum(x^2) - 1) * nnz`
+///
+/// It is zero when all nonzero entries of the column are equal, which is the case for converged columns.
+pub(crate) fn column_chaos<A: Float, I: Iterator<Item = A>>(values: I) -> A {
+    let (mut max, mut sum_sq, mut nnz): (A, A, usize) = (zero(), zero(), 0);
+
+    for x in values.filter(|&x| x != zero()) {
+        max = max.max(x.abs());
+        sum_sq = sum_sq + x * x;
+        nnz += 1;
+    }
+
+    if nnz == 0 {
+        return zero();
+    }
+    (max / sum_sq - A::one()) * A::from(nnz).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_column_chaos() {
+        assert_abs_diff_eq!(column_chaos(vec![0.5, 0.5, 0.].into_iter()), 0.);
+        assert_abs_diff_eq!(column_chaos(Vec::<f64>::new().into_iter()), 0.);
+        // max 0.75, sum of squares 0.625
+        assert_abs_diff_eq!(column_chaos(vec![0.75, 0.25].into_iter()), 0.4);
+    }
+}
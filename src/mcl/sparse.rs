@@ -0,0 +1,539 @@
+/*!
+Compressed sparse column (CSC) backend for [`MclExt`].
+
+MCL works column by column: every column of the flow matrix is a probability distribution,
+and inflation, normalization and pruning only look at one column at a time.
+`CscMatrix` stores only the nonzero entries of each column, so entries removed by pruning
+never take up memory again.
+
+```rust
+# #[macro_use] extern crate ndarray;
+# #[macro_use] extern crate approx;
+use graph_clustering_rs::mcl::*;
+use ndarray::Array2;
+
+let input: Array2<f64> = array![[1., 1., 1., 0., 0., 0., 0.],
+                                [1., 1., 1., 0., 0., 0., 0.],
+                                [1., 1., 1., 1., 0., 0., 0.],
+                                [0., 0., 1., 1., 1., 0., 1.],
+                                [0., 0., 0., 1., 1., 1., 1.],
+                                [0., 0., 0., 0., 1., 1., 1.],
+                                [0., 0., 0., 1., 1., 1., 1.]];
+let params = MclParams::default();
+let sparse = CscMatrix::from_dense(&input);
+let output = sparse.mcl(&params).unwrap();
+
+assert_abs_diff_eq!(output.to_dense(), input.mcl(&params).unwrap());
+assert_eq!(get_clusters(&output).unwrap(), vec![vec![0, 1, 2], vec![3, 4, 5, 6]]);
+```
+*/
+
+use std::iter::Sum;
+
+use anyhow::{ensure, Result};
+use ndarray::Array2;
+use num_traits::{one, zero, Float};
+use rayon::prelude::*;
+
+use super::{MclExt, NonzeroExt, PruneOptions, _handle_zeros_in_scale};
+use super::prune::prune_column;
+use super::report::column_chaos;
+
+/// Sparse matrix in compressed sparse column format
+///
+/// The row indices of each column are kept sorted and explicit zeros are never stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CscMatrix<A> {
+    nrows: usize,
+    ncols: usize,
+    indptr: Vec<usize>,
+    indices: Vec<usize>,
+    data: Vec<A>,
+}
+
+impl<A: Float + Send + Sync> CscMatrix<A> {
+    /// Create a matrix from raw CSC arrays
+    ///
+    /// `indptr` must have `ncols + 1` entries, and the row indices of each column must be sorted and unique.
+    pub fn new(shape: (usize, usize), indptr: Vec<usize>, indices: Vec<usize>, data: Vec<A>) -> Result<Self> {
+        let (nrows, ncols) = shape;
+        ensure!(indptr.len() == ncols + 1, "indptr must have {} entries, got {}", ncols + 1, indptr.len());
+        ensure!(indices.len() == data.len(), "indices and data must have the same length");
+        ensure!(indptr[0] == 0 && indptr[ncols] == data.len(), "indptr must start at 0 and end at nnz");
+        // with the first and last entries checked, this also bounds every entry by nnz
+        ensure!(indptr.windows(2).all(|w| w[0] <= w[1]), "indptr must be non-decreasing");
+
+        for j in 0..ncols {
+            let rows = &indices[indptr[j]..indptr[j + 1]];
+            ensure!(rows.windows(2).all(|w| w[0] < w[1]), "row indices of column {} are not sorted", j);
+            ensure!(rows.iter().all(|&i| i < nrows), "row index out of bounds in column {}", j);
+        }
+
+        let mut mat = Self { nrows, ncols, indptr, indices, data };
+        mat.eliminate_zeros();
+        Ok(mat)
+    }
+
+    /// Create an empty matrix
+    pub fn zeros(shape: (usize, usize)) -> Self {
+        Self {
+            nrows: shape.0,
+            ncols: shape.1,
+            indptr: vec![0; shape.1 + 1],
+            indices: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+
+    /// Create a matrix from `(row, col, value)` triplets. Duplicated entries are summed up.
+    ///
+    /// ```
+    /// use graph_clustering_rs::mcl::CscMatrix;
+    /// let mat = CscMatrix::from_triplets((2, 2), &[(0, 0, 1.), (1, 0, 2.), (1, 0, 3.)]).unwrap();
+    /// assert_eq!(mat.get(1, 0), 5.);
+    /// assert_eq!(mat.nnz(), 2);
+    /// ```
+    pub fn from_triplets(shape: (usize, usize), triplets: &[(usize, usize, A)]) -> Result<Self> {
+        let mut columns: Vec<Vec<(usize, A)>> = vec![Vec::new(); shape.1];
+
+        for &(i, j, x) in triplets.iter() {
+            ensure!(i < shape.0 && j < shape.1, "entry ({}, {}) is out of bounds for shape {:?}", i, j, shape);
+            columns[j].push((i, x));
+        }
+
+        for col in columns.iter_mut() {
+            col.sort_by_key(|&(i, _)| i);
+            col.dedup_by(|b, a| {
+                if a.0 == b.0 {
+                    a.1 = a.1 + b.1;
+                    true
+                } else {
+                    false
+                }
+            });
+        }
+
+        Ok(Self::from_columns(shape.0, columns))
+    }
+
+    /// Create a sparse matrix from a dense one
+    pub fn from_dense(matrix: &Array2<A>) -> Self {
+        let (nrows, ncols) = matrix.dim();
+        let columns = (0..ncols)
+            .map(|j| {
+                matrix.column(j)
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &x)| x != zero())
+                    .map(|(i, &x)| (i, x))
+                    .collect()
+            })
+            .collect();
+
+        Self::from_columns(nrows, columns)
+    }
+
+    /// Convert to a dense matrix
+    pub fn to_dense(&self) -> Array2<A> {
+        let mut mat = Array2::zeros((self.nrows, self.ncols));
+        for (i, j, x) in self.iter() {
+            mat[(i, j)] = x;
+        }
+        mat
+    }
+
+    /// Build a matrix from sorted `(row, value)` entries of each column
+    pub(crate) fn from_columns(nrows: usize, columns: Vec<Vec<(usize, A)>>) -> Self {
+        let ncols = columns.len();
+        let nnz = columns.iter().map(|c| c.len()).sum();
+
+        let mut indptr = Vec::with_capacity(ncols + 1);
+        let mut indices = Vec::with_capacity(nnz);
+        let mut data = Vec::with_capacity(nnz);
+
+        indptr.push(0);
+        for col in columns.into_iter() {
+            for (i, x) in col.into_iter() {
+                if x != zero() {
+                    indices.push(i);
+                    data.push(x);
+                }
+            }
+            indptr.push(indices.len());
+        }
+
+        Self { nrows, ncols, indptr, indices, data }
+    }
+
+    /// Apply `f` to every column and build a new matrix from the returned entries
+    pub(crate) fn map_columns<F>(&self, f: F) -> Self
+    where
+        F: Fn(&[usize], &[A]) -> Vec<(usize, A)> + Sync + Send,
+    {
+        let columns = (0..self.ncols)
+            .into_par_iter()
+            .map(|j| {
+                let (rows, values) = self.col(j);
+                f(rows, values)
+            })
+            .collect();
+
+        Self::from_columns(self.nrows, columns)
+    }
+
+    fn eliminate_zeros(&mut self) {
+        if self.data.iter().all(|&x| x != zero()) {
+            return;
+        }
+
+        let columns = (0..self.ncols)
+            .map(|j| {
+                let (rows, values) = self.col(j);
+                rows.iter().copied().zip(values.iter().copied()).collect()
+            })
+            .collect();
+        *self = Self::from_columns(self.nrows, columns);
+    }
+
+    /// Shape of the matrix as `(rows, cols)`
+    pub fn shape(&self) -> (usize, usize) {
+        (self.nrows, self.ncols)
+    }
+
+    /// Number of stored (nonzero) entries
+    pub fn nnz(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Row indices and values of the `j`-th column
+    pub fn col(&self, j: usize) -> (&[usize], &[A]) {
+        let (start, end) = (self.indptr[j], self.indptr[j + 1]);
+        (&self.indices[start..end], &self.data[start..end])
+    }
+
+    /// Value at `(i, j)`, zero if the entry is not stored
+    pub fn get(&self, i: usize, j: usize) -> A {
+        let (rows, values) = self.col(j);
+        match rows.binary_search(&i) {
+            Ok(k) => values[k],
+            Err(_) => zero(),
+        }
+    }
+
+    /// Iterate over `(row, col, value)` of the stored entries in column-major order
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, A)> + '_ {
+        (0..self.ncols).flat_map(move |j| {
+            let (rows, values) = self.col(j);
+            rows.iter().zip(values.iter()).map(move |(&i, &x)| (i, j, x))
+        })
+    }
+
+    /// Matrix product `self * rhs`
+    pub fn dot(&self, rhs: &Self) -> Result<Self> {
+        ensure!(self.ncols == rhs.nrows, "shape mismatch: {:?} x {:?}", self.shape(), rhs.shape());
+
+        // every worker keeps its own dense accumulator, so each column is summed in the same order
+        // whatever the number of threads
+        let columns = (0..rhs.ncols)
+            .into_par_iter()
+            .map_init(
+                || (vec![zero(); self.nrows], vec![false; self.nrows]),
+                |(acc, seen), j| {
+                    let (rows, values) = rhs.col(j);
+                    self._column_product(rows, values, acc, seen)
+                },
+            )
+            .collect();
+
+        Ok(Self::from_columns(self.nrows, columns))
+    }
+
+    /// Compute `self * v` for a sparse vector `v` with a dense accumulator
+    fn _column_product(&self, rows: &[usize], values: &[A], acc: &mut [A], seen: &mut [bool]) -> Vec<(usize, A)> {
+        let mut touched: Vec<usize> = Vec::new();
+
+        for (&k, &b) in rows.iter().zip(values.iter()) {
+            let (k_rows, k_values) = self.col(k);
+            for (&i, &a) in k_rows.iter().zip(k_values.iter()) {
+                if !seen[i] {
+                    seen[i] = true;
+                    touched.push(i);
+                }
+                acc[i] = acc[i] + a * b;
+            }
+        }
+
+        touched.sort_unstable();
+        touched
+            .into_iter()
+            .map(|i| {
+                let x = acc[i];
+                acc[i] = zero();
+                seen[i] = false;
+                (i, x)
+            })
+            .collect()
+    }
+}
+
+impl<A: Float + Send + Sync> NonzeroExt<A> for CscMatrix<A> {
+    fn nonzero_entries(&self) -> Vec<(usize, usize, A)> {
+        self.iter().collect()
+    }
+
+    fn node_count(&self) -> usize {
+        self.shape().1
+    }
+}
+
+impl<A> MclExt<A> for CscMatrix<A>
+where
+    A: 'static + Float + Sum + Send + Sync,
+{
+    fn normalize(&self) -> Result<Self> {
+        Ok(self.map_columns(|rows, values| {
+            let norm_l1: A = _handle_zeros_in_scale(values.iter().map(|x| x.abs()).sum());
+            rows.iter().zip(values.iter()).map(|(&i, &x)| (i, x / norm_l1)).collect()
+        }))
+    }
+
+    fn matmul(&self, other: &Self) -> Result<Self> {
+        self.dot(other)
+    }
+
+    fn interpolate(&self, other: &Self, t: A) -> Result<Self> {
+        ensure!(self.shape() == other.shape(), "shape mismatch: {:?} and {:?}", self.shape(), other.shape());
+
+        let columns = (0..self.ncols)
+            .into_par_iter()
+            .map(|j| {
+                _merge_columns(self.col(j), other.col(j))
+                    .into_iter()
+                    .map(|(i, a, b)| (i, a * (one::<A>() - t) + b * t))
+                    .collect()
+            })
+            .collect();
+
+        Ok(Self::from_columns(self.nrows, columns))
+    }
+
+    fn inflate(&self, power: A) -> Result<Self> {
+        self.map_columns(|rows, values| {
+            rows.iter().zip(values.iter()).map(|(&i, &x)| (i, x.powf(power))).collect()
+        })
+        .normalize()
+    }
+
+    fn prune_with(&self, options: &PruneOptions<A>) -> Result<Self> {
+        Ok(self.map_columns(|rows, values| prune_column(rows, values, options)))
+    }
+
+    fn add_self_loop(&mut self, loop_value: A) -> Result<()> {
+        ensure!(self.nrows == self.ncols, "self loops need a square matrix, got {:?}", self.shape());
+
+        let columns = (0..self.ncols)
+            .into_par_iter()
+            .map(|j| {
+                let (rows, values) = self.col(j);
+                let mut col: Vec<(usize, A)> = rows.iter()
+                    .copied()
+                    .zip(values.iter().copied())
+                    .filter(|&(i, _)| i != j)
+                    .collect();
+                let pos = col.partition_point(|&(i, _)| i < j);
+                col.insert(pos, (j, loop_value));
+                col
+            })
+            .collect();
+
+        *self = Self::from_columns(self.nrows, columns);
+        Ok(())
+    }
+
+    fn converged(&self, other: &Self) -> bool {
+        if self.shape() != other.shape() {
+            return false;
+        }
+
+        let tol = A::from(1e-8).unwrap();
+        (0..self.ncols).into_par_iter().all(|j| {
+            _merge_columns(self.col(j), other.col(j))
+                .into_iter()
+                .all(|(_, a, b)| (a - b).abs() <= tol)
+        })
+    }
+
+    fn chaos(&self) -> A {
+        (0..self.ncols)
+            .into_par_iter()
+            .map(|j| column_chaos(self.col(j).1.iter().copied()))
+            .reduce(zero, |a, b| a.max(b))
+    }
+
+    fn change_norm(&self, other: &Self) -> A {
+        // per-column sums are added up in column order, so the norm does not depend on the thread count
+        let sums: Vec<A> = (0..self.ncols)
+            .into_par_iter()
+            .map(|j| {
+                _merge_columns(self.col(j), other.col(j))
+                    .into_iter()
+                    .map(|(_, a, b)| (a - b) * (a - b))
+                    .sum()
+            })
+            .collect();
+        sums.into_iter().sum::<A>().sqrt()
+    }
+
+    fn nnz(&self) -> usize {
+        self.data.len()
+    }
+}
+
+/// Align two sparse columns by row, filling missing entries with zero
+fn _merge_columns<A: Float>(a: (&[usize], &[A]), b: (&[usize], &[A])) -> Vec<(usize, A, A)> {
+    let ((a_rows, a_values), (b_rows, b_values)) = (a, b);
+    let mut merged = Vec::with_capacity(a_rows.len().max(b_rows.len()));
+
+    let (mut p, mut q) = (0, 0);
+    while p < a_rows.len() || q < b_rows.len() {
+        if q == b_rows.len() || (p < a_rows.len() && a_rows[p] < b_rows[q]) {
+            merged.push((a_rows[p], a_values[p], zero()));
+            p += 1;
+        } else if p == a_rows.len() || b_rows[q] < a_rows[p] {
+            merged.push((b_rows[q], zero(), b_values[q]));
+            q += 1;
+        } else {
+            merged.push((a_rows[p], a_values[p], b_values[q]));
+            p += 1;
+            q += 1;
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mcl::{get_clusters, MclParams};
+    use approx::assert_abs_diff_eq;
+
+    fn mcl_input() -> Array2<f64> {
+        array![[1., 1., 1., 0., 0., 0., 0.],
+               [1., 1., 1., 0., 0., 0., 0.],
+               [1., 1., 1., 1., 0., 0., 0.],
+               [0., 0., 1., 1., 1., 0., 1.],
+               [0., 0., 0., 1., 1., 1., 1.],
+               [0., 0., 0., 0., 1., 1., 1.],
+               [0., 0., 0., 1., 1., 1., 1.]]
+    }
+
+    #[test]
+    fn test_dense_roundtrip() {
+        let input = mcl_input();
+        let sparse = CscMatrix::from_dense(&input);
+        assert_eq!(sparse.nnz(), 25);
+        assert_abs_diff_eq!(sparse.to_dense(), input);
+    }
+
+    #[test]
+    fn test_from_triplets_out_of_bounds() {
+        assert!(CscMatrix::from_triplets((2, 2), &[(2, 0, 1.)]).is_err());
+    }
+
+    #[test]
+    fn test_new() {
+        let mat = CscMatrix::new((2, 2), vec![0, 1, 2], vec![1, 0], vec![1., 2.]).unwrap();
+        assert_eq!(mat.get(1, 0), 1.);
+        assert_eq!(mat.get(0, 1), 2.);
+
+        assert!(CscMatrix::new((2, 2), vec![0, 5, 2], vec![0, 1], vec![1., 2.]).is_err());
+        assert!(CscMatrix::new((2, 2), vec![0, 2, 2], vec![1, 0], vec![1., 2.]).is_err());
+        assert!(CscMatrix::new((2, 2), vec![0, 1, 2], vec![2, 0], vec![1., 2.]).is_err());
+    }
+
+    #[test]
+    fn test_dot() {
+        let a: Array2<f64> = array![[1., 0., 2.], [0., 3., 0.], [4., 0., 5.]];
+        let b: Array2<f64> = array![[0., 1.], [2., 0.], [0., 3.]];
+        let c = CscMatrix::from_dense(&a).dot(&CscMatrix::from_dense(&b)).unwrap();
+        assert_abs_diff_eq!(c.to_dense(), a.dot(&b));
+    }
+
+    #[test]
+    fn test_normalize_inflate_expand() {
+        let input = mcl_input();
+        let sparse = CscMatrix::from_dense(&input);
+        assert_abs_diff_eq!(sparse.normalize().unwrap().to_dense(), input.normalize().unwrap());
+        assert_abs_diff_eq!(sparse.inflate(2.).unwrap().to_dense(), input.inflate(2.).unwrap());
+        assert_abs_diff_eq!(sparse.expand(3).unwrap().to_dense(), input.expand(3).unwrap());
+        assert_abs_diff_eq!(sparse.expand(4).unwrap().to_dense(), input.expand(4).unwrap());
+    }
+
+    #[test]
+    fn test_expand_pruned() {
+        let input = mcl_input().normalize().unwrap();
+        let options = PruneOptions::new(0.05);
+        let sparse = CscMatrix::from_dense(&input).expand_pruned(2.5, &options).unwrap();
+        assert_abs_diff_eq!(sparse.to_dense(), input.expand_pruned(2.5, &options).unwrap(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_pruned() {
+        let matrix: Array2<f64> = array![[1., 2., 3.], [3., 1., 4.]];
+        let pruned = CscMatrix::from_dense(&matrix).prune(2.5).unwrap();
+        assert_eq!(pruned.nnz(), 4);
+        assert_abs_diff_eq!(pruned.to_dense(), array![[0., 2., 3.,], [3., 0., 4.]]);
+    }
+
+    #[test]
+    fn test_prune_with() {
+        let input = mcl_input().normalize().unwrap().expand(2).unwrap();
+        let options = PruneOptions::new(0.1).select(3).recover(4).pct(0.9);
+        let pruned = CscMatrix::from_dense(&input).prune_with(&options).unwrap();
+        assert!((0..7).all(|j| pruned.col(j).0.len() <= 4));
+        assert_abs_diff_eq!(pruned.to_dense(), input.prune_with(&options).unwrap());
+    }
+
+    #[test]
+    fn test_add_self_loop() {
+        let mut sparse = CscMatrix::from_dense(&array![[0., 1.], [1., 2.]]);
+        sparse.add_self_loop(1.).unwrap();
+        assert_abs_diff_eq!(sparse.to_dense(), array![[1., 1.], [1., 1.]]);
+    }
+
+    #[test]
+    fn test_dot_thread_count() {
+        let n = 200;
+        let triplets: Vec<(usize, usize, f64)> = (0..n)
+            .flat_map(|i| (0..n).filter(move |j| (i * 7 + j * 13) % 11 == 0).map(move |j| (i, j, 1. / (1 + i + j) as f64)))
+            .collect();
+        let mat = CscMatrix::from_triplets((n, n), &triplets).unwrap();
+
+        let run = |threads: usize| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| mat.dot(&mat).unwrap().inflate(2.).unwrap())
+        };
+
+        assert_eq!(run(1), run(8));
+    }
+
+    #[test]
+    fn test_mcl() {
+        let input = mcl_input();
+        let params = MclParams::builder().pruning(PruneOptions::new(0.001)).build().unwrap();
+        let dense = input.mcl(&params).unwrap();
+        let sparse = CscMatrix::from_dense(&input).mcl(&params).unwrap();
+        assert_abs_diff_eq!(sparse.to_dense(), dense, epsilon = 1e-12);
+        assert_eq!(get_clusters(&sparse).unwrap(), get_clusters(&dense).unwrap());
+
+        let (_, dense_report) = input.mcl_with_report(&params).unwrap();
+        let (_, sparse_report) = CscMatrix::from_dense(&input).mcl_with_report(&params).unwrap();
+        assert_eq!(sparse_report.iterations, dense_report.iterations);
+        assert_eq!(sparse_report.nnz, dense_report.nnz);
+        assert_abs_diff_eq!(sparse_report.chaos.as_slice(), dense_report.chaos.as_slice(), epsilon = 1e-12);
+    }
+}
@@ -1,9 +1,104 @@
-use std::{collections::{HashSet}, iter::Sum};
+/*!
+MCODE (Molecular Complex Detection) finds densely connected regions of a network. It was developed by Bader & Hogue,
+details of the algorithm can be found in [An automated method for finding molecular complexes in large protein interaction networks](https://doi.org/10.1186/1471-2105-4-2).
+
+The algorithm runs in three steps:
+
+1. vertex weighting: the weight of a vertex is the core number of the highest k-core of its neighborhood times the density of that core
+2. complex prediction: starting from the heaviest unseen vertex, vertices whose weight is at least `(1 - node_score_cutoff)` times the seed weight are added recursively
+3. post-processing: complexes without a k-core are discarded, and the complexes are optionally trimmed by the haircut and grown by the fluff step
+
+Each complex is scored by its density times its number of vertices.
+Edge weights are used as the weights of the edges in the densities, so an unweighted network should have every edge weight set to 1.
+
+# Example Usage
+
+```rust
+use graph_clustering_rs::mcode::*;
+use petgraph::graph::{NodeIndex, UnGraph};
+
+// two cliques joined by the edge 3-4
+let graph = UnGraph::<f64, f64>::from_edges(&[
+    (0, 1, 1.), (0, 2, 1.), (0, 3, 1.), (1, 2, 1.), (1, 3, 1.), (2, 3, 1.),
+    (3, 4, 1.),
+    (4, 5, 1.), (4, 6, 1.), (5, 6, 1.),
+]);
+
+let complexes = graph.mcode(&McodeParams::default()).unwrap();
+assert_eq!(complexes.len(), 2);
+assert_eq!(complexes[0].score, 4.);
+assert!(complexes[0].nodes.contains(&NodeIndex::new(0)));
+assert_eq!(complexes[1].score, 3.);
+assert!(complexes[1].nodes.contains(&NodeIndex::new(4)));
+```
+*/
+
+use std::{cmp::Ordering, collections::{HashSet}, iter::Sum, time::Instant};
+use anyhow::Result;
 use petgraph::{EdgeType, graph::{Graph, IndexType, NodeIndex}};
-use num_traits::{Float};
+use num_traits::{Float, zero};
+
+use crate::clustering::{Clustering, ToClustering};
+use crate::graph_utils::{density, sum_edge_weights};
+use crate::observer::{Flow, Observer, Progress};
+
+pub mod params;
+
+pub use params::{McodeParams, McodeParamsBuilder};
+
+/// State of the vertex weighting after one node, passed to an [`Observer`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightingState<W, Ix> {
+    /// Node weighted in this step
+    pub node: NodeIndex<Ix>,
+    /// Weight of the node
+    pub weight: W,
+}
+
+/// Complex found by MCODE
+#[derive(Debug, Clone)]
+pub struct McodeComplex<W, Ix> {
+    /// Vertices of the complex
+    pub nodes: HashSet<NodeIndex<Ix>>,
+    /// Density of the complex times its number of vertices
+    pub score: W,
+    /// Vertex the complex was grown from
+    pub seed: NodeIndex<Ix>,
+}
+
+impl<W: PartialEq, Ix: IndexType> PartialEq for McodeComplex<W, Ix> {
+    fn eq(&self, other: &Self) -> bool {
+        self.nodes == other.nodes && self.score == other.score && self.seed == other.seed
+    }
+}
+
+/// Complexes with their scores
+impl<W: Float, Ix: IndexType> ToClustering for [McodeComplex<W, Ix>] {
+    fn clustering(&self, node_count: usize) -> Result<Clustering> {
+        let clusters = self.iter()
+            .map(|c| c.nodes.iter().map(|n| n.index()).collect())
+            .collect();
+        let scores = self.iter().map(|c| c.score.to_f64().unwrap_or(f64::NAN)).collect();
+        Clustering::new(node_count, clusters)?.with_scores(scores)
+    }
+}
 
-trait McodeExt<W, Ty, Ix> {
+pub trait McodeExt<W, Ty, Ix> {
+    /// Weight every vertex of the graph
     fn vertex_weighting(&mut self);
+
+    /// Weight every vertex of the graph, calling `observer` after each vertex
+    ///
+    /// Returns `false` if the observer stopped the weighting before all vertices were weighted.
+    fn vertex_weighting_observed<O: Observer<WeightingState<W, Ix>>>(&mut self, observer: &mut O) -> bool;
+
+    /// Find complexes in a graph whose node weights were already set by [`vertex_weighting`](McodeExt::vertex_weighting)
+    ///
+    /// Complexes are sorted by decreasing score.
+    fn find_complexes(&self, params: &McodeParams<W>) -> Result<Vec<McodeComplex<W, Ix>>>;
+
+    /// Weight a copy of the graph and find its complexes
+    fn mcode(&self, params: &McodeParams<W>) -> Result<Vec<McodeComplex<W, Ix>>>;
 }
 
 impl<W, Ty, Ix> McodeExt<W, Ty, Ix> for Graph<W, W, Ty, Ix>
@@ -13,52 +108,108 @@ where
     Ix: IndexType
 {
     fn vertex_weighting(&mut self) {
-        _get_node_weight_from_edges(self);
+        self.vertex_weighting_observed(&mut |_: &Progress<WeightingState<W, Ix>>| Flow::Continue);
+    }
 
-        for node in self.node_indices() {
-            let mut neighborhood: HashSet<NodeIndex<Ix>> = std::iter::once(node).chain(self.neighbors(node)).collect();
+    fn vertex_weighting_observed<O: Observer<WeightingState<W, Ix>>>(&mut self, observer: &mut O) -> bool {
+        let start = Instant::now();
+        _get_node_weight_from_edges(self);
 
-            if neighborhood.len() <= 2 { continue; }
+        let total = self.node_count();
+        for (i, node) in self.node_indices().enumerate() {
+            let mut neighborhood: HashSet<NodeIndex<Ix>> = std::iter::once(node).chain(self.neighbors_undirected(node)).collect();
 
-            let mut k = 2;
+            if neighborhood.len() > 1 {
+                // find the highest k-core of the neighborhood
+                let mut k = 1;
+                let mut core = None;
 
-            loop {
-                neighborhood = _update_neighborhood(&neighborhood, k, self);
+                loop {
+                    neighborhood = _update_neighborhood(&neighborhood, k, self);
 
-                if neighborhood.is_empty() { break; }
+                    if neighborhood.is_empty() { break; }
 
-                let new_weight = _make_new_weight(W::from(k).unwrap(), node, &neighborhood, self);
+                    core = Some((k, neighborhood.clone()));
+                    k += 1;
+                }
 
-                match self.node_weight_mut(node) {
-                    Some(w) => {
-                        if *w > new_weight {
-                            *w = new_weight;
-                        }
-                    },
-                    None => {}
+                if let Some((k, core)) = core {
+                    self[node] = _make_new_weight(W::from(k).unwrap(), &core, self);
                 }
+            }
 
-                k += 1;
+            let progress = Progress {
+                iteration: i + 1,
+                total: Some(total),
+                elapsed: start.elapsed(),
+                state: WeightingState { node, weight: self[node] },
+            };
+            if observer.observe(&progress) == Flow::Stop {
+                return false;
             }
         }
+
+        true
     }
-}
 
+    fn find_complexes(&self, params: &McodeParams<W>) -> Result<Vec<McodeComplex<W, Ix>>> {
+        params.validate()?;
+
+        // seeds in order of decreasing weight, ties in order of index
+        let mut seeds: Vec<NodeIndex<Ix>> = self.node_indices().collect();
+        seeds.sort_by(|&a, &b| self[b].partial_cmp(&self[a]).unwrap_or(Ordering::Equal).then(a.cmp(&b)));
+
+        let mut seen: HashSet<NodeIndex<Ix>> = HashSet::new();
+        let mut complexes = Vec::new();
+
+        for seed in seeds {
+            if seen.contains(&seed) { continue; }
+
+            let mut nodes = _grow_complex(seed, params, &mut seen, self);
+
+            if let Some(k) = params.k_core {
+                if _k_core(&nodes, k, self).is_empty() { continue; }
+            }
+
+            if params.haircut {
+                nodes = _k_core(&nodes, 2, self);
+            }
+
+            if params.fluff {
+                nodes = _fluff(&nodes, params.fluff_node_density_cutoff, self);
+            }
+
+            if nodes.is_empty() { continue; }
+
+            let score = density(&nodes, self) * W::from(nodes.len()).unwrap();
+            complexes.push(McodeComplex { nodes, score, seed });
+        }
+
+        complexes.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal).then(a.seed.cmp(&b.seed)));
+
+        Ok(complexes)
+    }
+
+    fn mcode(&self, params: &McodeParams<W>) -> Result<Vec<McodeComplex<W, Ix>>> {
+        params.validate()?;
+
+        let mut graph = self.clone();
+        graph.vertex_weighting();
+        graph.find_complexes(params)
+    }
+}
 
 /// vertex weight = k-core number * density of k-core
-fn _make_new_weight<W, Ty, Ix>(k: W, node: NodeIndex<Ix>, neighborhood: &HashSet<NodeIndex<Ix>>, graph: &Graph<W, W, Ty, Ix>) -> W
+fn _make_new_weight<W, Ty, Ix>(k: W, core: &HashSet<NodeIndex<Ix>>, graph: &Graph<W, W, Ty, Ix>) -> W
 where
     W: Float + Sum,
     Ty: EdgeType,
     Ix: IndexType
 {
-    k * neighborhood.iter()
-        .filter_map(|&x| graph.find_edge(node, x))
-        .filter_map(|e| graph.edge_weight(e))
-        .map(|x| *x)
-        .sum::<W>() / W::from(neighborhood.len().pow(2u32)).unwrap()
+    k * density(core, graph)
 }
 
+/// One pass of k-core peeling: remove nodes with less than `k` neighbors in the set until none is removed
 fn _update_neighborhood<W, Ty, Ix>(neighborhood: &HashSet<NodeIndex<Ix>>, k: usize, graph: &Graph<W, W, Ty, Ix>) -> HashSet<NodeIndex<Ix>>
 where
     W: Float + Sum,
@@ -67,25 +218,87 @@ where
 {
     let mut flag = true;
     let mut neighborhood = neighborhood.clone();
-    let mut invalid_nodes = HashSet::new();
     while flag && !neighborhood.is_empty() {
-        for &n in neighborhood.clone().iter() {
-            let n_neighborhood: HashSet<NodeIndex<Ix>> = graph.neighbors(n).collect();
-            // dbg!(&n_neighborhood);
+        let mut invalid_nodes = HashSet::new();
+        for &n in neighborhood.iter() {
+            let n_neighborhood: HashSet<NodeIndex<Ix>> = graph.neighbors_undirected(n).filter(|&x| x != n).collect();
 
             if n_neighborhood.intersection(&neighborhood).count() < k {
                 invalid_nodes.insert(n);
             }
         }
 
-        // dbg!(&invalid_nodes);
-        neighborhood = neighborhood.difference(&invalid_nodes).map(|x| *x).collect();
+        neighborhood = neighborhood.difference(&invalid_nodes).copied().collect();
         flag = !invalid_nodes.is_empty();
     }
 
     neighborhood
 }
 
+/// k-core of the subgraph induced by `nodes`, empty if there is none
+fn _k_core<W, Ty, Ix>(nodes: &HashSet<NodeIndex<Ix>>, k: usize, graph: &Graph<W, W, Ty, Ix>) -> HashSet<NodeIndex<Ix>>
+where
+    W: Float + Sum,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    _update_neighborhood(nodes, k, graph)
+}
+
+/// Add the vertices reachable from `seed` within `max_depth` steps whose weight passes the node score cutoff
+fn _grow_complex<W, Ty, Ix>(seed: NodeIndex<Ix>, params: &McodeParams<W>, seen: &mut HashSet<NodeIndex<Ix>>, graph: &Graph<W, W, Ty, Ix>) -> HashSet<NodeIndex<Ix>>
+where
+    W: Float + Sum,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    let cutoff = (W::one() - params.node_score_cutoff) * graph[seed];
+
+    let mut complex = HashSet::new();
+    let mut stack = vec![(seed, 0)];
+    seen.insert(seed);
+    complex.insert(seed);
+
+    while let Some((node, depth)) = stack.pop() {
+        if depth >= params.max_depth { continue; }
+
+        for neighbor in graph.neighbors_undirected(node) {
+            if !seen.contains(&neighbor) && graph[neighbor] >= cutoff {
+                seen.insert(neighbor);
+                complex.insert(neighbor);
+                stack.push((neighbor, depth + 1));
+            }
+        }
+    }
+
+    complex
+}
+
+/// Add the neighbors of the complex whose closed neighborhood is denser than `cutoff`
+///
+/// Fluffed vertices are not marked as seen, so they may also belong to other complexes.
+fn _fluff<W, Ty, Ix>(nodes: &HashSet<NodeIndex<Ix>>, cutoff: W, graph: &Graph<W, W, Ty, Ix>) -> HashSet<NodeIndex<Ix>>
+where
+    W: Float + Sum,
+    Ty: EdgeType,
+    Ix: IndexType
+{
+    let mut fluffed = nodes.clone();
+
+    for &node in nodes.iter() {
+        for neighbor in graph.neighbors_undirected(node) {
+            if fluffed.contains(&neighbor) { continue; }
+
+            let neighborhood: HashSet<NodeIndex<Ix>> = std::iter::once(neighbor).chain(graph.neighbors_undirected(neighbor)).collect();
+            if density(&neighborhood, graph) > cutoff {
+                fluffed.insert(neighbor);
+            }
+        }
+    }
+
+    fluffed
+}
+
 fn _get_node_weight_from_edges<W, Ty, Ix>(graph: &mut Graph<W, W, Ty, Ix>)
 where
     W: Float + Sum,
@@ -93,10 +306,14 @@ where
     Ix: IndexType
 {
     for node in graph.node_indices() {
-        let weight: W = graph.edges(node).map(|e| *e.weight()).sum::<W>() / W::from(graph.neighbors(node).count().pow(2u32)).unwrap();
-        match graph.node_weight_mut(node) {
-            Some(w) => { *w = weight},
-            None => {}
+        let degree = graph.neighbors_undirected(node).count();
+        let weight: W = if degree == 0 {
+            zero()
+        } else {
+            sum_edge_weights(node, graph) / W::from(degree.pow(2u32)).unwrap()
+        };
+        if let Some(w) = graph.node_weight_mut(node) {
+            *w = weight;
         }
     }
 }
@@ -104,11 +321,12 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::graph_utils::{bridged_cliques, node_set};
     use approx::assert_abs_diff_eq;
     use petgraph::{Undirected};
 
     fn graph1() -> Graph::<f64, f64, Undirected, usize> {
-        Graph::<f64, f64, Undirected, usize>::from_edges(&[
+        Graph::<f64, f64, Undirected, usize>::from_edges([
             (0, 1, 1.), (0, 2, 0.7), (0, 3, 0.7),
             (1, 2, 1.), (1, 3, 0.7),
             (2, 3, 0.8),
@@ -125,14 +343,79 @@ mod test {
         assert_abs_diff_eq!(*gr1.node_weight(NodeIndex::new(3)).unwrap(), 0.24444444444444444);
     }
 
+    #[test]
+    fn test_vertex_weighting_observed_stop() {
+        let mut gr1 = graph1();
+        let mut seen = Vec::new();
+        let mut observer = |p: &Progress<WeightingState<f64, usize>>| {
+            seen.push(p.state.node.index());
+            if p.iteration == 2 { Flow::Stop } else { Flow::Continue }
+        };
+        assert!(!gr1.vertex_weighting_observed(&mut observer));
+        assert_eq!(seen, vec![0, 1]);
+
+        let mut gr1 = graph1();
+        assert!(gr1.vertex_weighting_observed(&mut crate::observer::Budget::new()));
+    }
+
     #[test]
     fn test_update_neighbors() {
         let gr1 = graph1();
         let node: NodeIndex<usize> = NodeIndex::new(0);
 
         let mut neighborhood: HashSet<NodeIndex<usize>> = std::iter::once(node).chain(gr1.neighbors(node)).collect();
-        dbg!(1, &neighborhood);
         neighborhood = _update_neighborhood(&neighborhood, 2, &gr1);
-        dbg!(2, &neighborhood);
+        assert_eq!(neighborhood, node_set(&[0, 1, 2, 3]));
+        assert!(_update_neighborhood(&neighborhood, 4, &gr1).is_empty());
+    }
+
+    #[test]
+    fn test_vertex_weighting() {
+        let mut gr1 = graph1();
+        gr1.vertex_weighting();
+        // the neighborhood of every node is the whole K4, a 3-core with density 4.9 / 6
+        for node in gr1.node_indices() {
+            assert_abs_diff_eq!(gr1[node], 3. * 4.9 / 6., epsilon = 1e-12);
+        }
+
+        let mut gr2 = bridged_cliques();
+        gr2.vertex_weighting();
+        let weights: Vec<f64> = gr2.node_indices().map(|n| gr2[n]).collect();
+        assert_eq!(weights, vec![4., 4., 4., 4., 4., 2., 2., 2., 1.]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_mcode() {
+        let gr2 = bridged_cliques();
+        let complexes = gr2.mcode(&McodeParams::default()).unwrap();
+
+        assert_eq!(complexes.len(), 2);
+        assert_eq!(complexes[0].nodes, node_set(&[0, 1, 2, 3, 4]));
+        assert_eq!(complexes[0].seed, NodeIndex::new(0));
+        assert_abs_diff_eq!(complexes[0].score, 5.);
+        assert_eq!(complexes[1].nodes, node_set(&[5, 6, 7]));
+        assert_abs_diff_eq!(complexes[1].score, 3.);
+    }
+
+    #[test]
+    fn test_mcode_fluff() {
+        let gr2 = bridged_cliques();
+        let params = McodeParams::builder().fluff(true).build().unwrap();
+        let complexes = gr2.mcode(&params).unwrap();
+
+        // fluffed vertices may belong to several complexes
+        assert_eq!(complexes[0].nodes, node_set(&[0, 1, 2, 3, 4, 5, 8]));
+        assert_eq!(complexes[1].nodes, node_set(&[4, 5, 6, 7]));
+    }
+
+    #[test]
+    fn test_mcode_k_core_filter() {
+        // a path has no 2-core
+        let path = Graph::<f64, f64, Undirected, usize>::from_edges([(0, 1, 1.), (1, 2, 1.)]);
+        assert!(path.mcode(&McodeParams::default()).unwrap().is_empty());
+
+        let params = McodeParams::builder().k_core(None).haircut(false).node_score_cutoff(0.5).build().unwrap();
+        let complexes = path.mcode(&params).unwrap();
+        assert_eq!(complexes[0].nodes, node_set(&[0, 1, 2]));
+    }
+}
@@ -0,0 +1,124 @@
+/*!
+Parameters of [`McodeExt::mcode`](super::McodeExt::mcode).
+
+The defaults follow the MCODE plugin of Cytoscape: node score cutoff 0.2, haircut on,
+fluff off with a node density cutoff of 0.1, k-core filter with k = 2 and maximum depth 100.
+
+```rust
+use graph_clustering_rs::mcode::*;
+
+let params: McodeParams<f64> = McodeParams::builder()
+    .fluff(true)
+    .k_core(None)
+    .build()
+    .unwrap();
+assert!(params.haircut);
+
+assert!(McodeParams::<f64>::builder().node_score_cutoff(1.5).build().is_err());
+```
+*/
+
+use anyhow::{ensure, Result};
+use num_traits::{Float, one, zero};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Parameters of MCODE
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, bound(deserialize = "W: Float + Deserialize<'de>")))]
+pub struct McodeParams<W> {
+    /// Vertices whose weight is at least `(1 - node_score_cutoff)` times the seed weight join the complex
+    pub node_score_cutoff: W,
+    /// Remove the vertices singly connected to the complex
+    pub haircut: bool,
+    /// Add the neighbors of the complex whose neighborhood is denser than `fluff_node_density_cutoff`
+    pub fluff: bool,
+    /// Density cutoff of the fluff step
+    pub fluff_node_density_cutoff: W,
+    /// Discard complexes without a k-core of this k, no filtering if `None`
+    pub k_core: Option<usize>,
+    /// Maximum distance from the seed while growing a complex
+    pub max_depth: usize,
+}
+
+impl<W: Float> Default for McodeParams<W> {
+    fn default() -> Self {
+        Self {
+            node_score_cutoff: W::from(0.2).unwrap(),
+            haircut: true,
+            fluff: false,
+            fluff_node_density_cutoff: W::from(0.1).unwrap(),
+            k_core: Some(2),
+            max_depth: 100,
+        }
+    }
+}
+
+impl<W: Float> McodeParams<W> {
+    /// Start building parameters from the defaults
+    pub fn builder() -> McodeParamsBuilder<W> {
+        McodeParamsBuilder {
+            params: Self::default(),
+        }
+    }
+
+    /// Check that the parameters can be used to run MCODE
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.node_score_cutoff >= zero() && self.node_score_cutoff <= one(), "node_score_cutoff must be in [0, 1]");
+        ensure!(self.fluff_node_density_cutoff >= zero() && self.fluff_node_density_cutoff <= one(), "fluff_node_density_cutoff must be in [0, 1]");
+        ensure!(self.max_depth > 0, "max_depth must be at least 1");
+        Ok(())
+    }
+}
+
+/// Builder of [`McodeParams`]
+#[derive(Debug, Clone)]
+pub struct McodeParamsBuilder<W> {
+    params: McodeParams<W>,
+}
+
+impl<W: Float> McodeParamsBuilder<W> {
+    /// Set the node score cutoff of the complex growth
+    pub fn node_score_cutoff(mut self, node_score_cutoff: W) -> Self {
+        self.params.node_score_cutoff = node_score_cutoff;
+        self
+    }
+
+    /// Remove the vertices singly connected to the complexes
+    pub fn haircut(mut self, haircut: bool) -> Self {
+        self.params.haircut = haircut;
+        self
+    }
+
+    /// Add the dense neighbors of the complexes
+    pub fn fluff(mut self, fluff: bool) -> Self {
+        self.params.fluff = fluff;
+        self
+    }
+
+    /// Set the density cutoff of the fluff step
+    pub fn fluff_node_density_cutoff(mut self, fluff_node_density_cutoff: W) -> Self {
+        self.params.fluff_node_density_cutoff = fluff_node_density_cutoff;
+        self
+    }
+
+    /// Set the k of the k-core filter, `None` disables it
+    pub fn k_core(mut self, k_core: Option<usize>) -> Self {
+        self.params.k_core = k_core;
+        self
+    }
+
+    /// Set the maximum distance from the seed while growing a complex
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.params.max_depth = max_depth;
+        self
+    }
+
+    /// Validate and return the parameters
+    pub fn build(self) -> Result<McodeParams<W>> {
+        self.params.validate()?;
+        Ok(self.params)
+    }
+}
@@ -0,0 +1,343 @@
+/*!
+Quality of a clustering measured on the clustered graph, without a reference clustering.
+
+[`GraphMetrics`] holds the weighted undirected graph, built from a petgraph [`Graph`] or an adjacency matrix, and scores any
+[`Clustering`] of its nodes:
+
+| metric | scope | definition |
+| ------ | ----- | ---------- |
+| [`modularity`](GraphMetrics::modularity) | partition | weight inside the clusters minus its expectation in the configuration model scaled by the resolution |
+| [`coverage`](GraphMetrics::coverage) | clustering | fraction of the edge weight inside the clusters |
+| [`performance`](GraphMetrics::performance) | clustering | fraction of the node pairs that are linked inside a cluster or unlinked across clusters |
+| [`conductance`](GraphMetrics::conductance) | cluster | weight leaving the cluster over the smaller of the volumes of the cluster and the rest |
+| [`cut_ratio`](GraphMetrics::cut_ratio) | cluster | weight leaving the cluster over the number of pairs of a member and a non-member |
+| [`intra_cluster_density`](GraphMetrics::intra_cluster_density) | cluster | weight inside the cluster over the number of pairs of its members |
+| [`inter_cluster_density`](GraphMetrics::inter_cluster_density) | clustering | weight between clusters over the number of pairs of nodes that share no cluster |
+
+Edge directions are ignored, and unassigned nodes are treated as clusters of their own by the clustering wide metrics.
+Clusters may overlap, two nodes are then in the same cluster if they share one. Only modularity requires a partition.
+Performance counts pairs with an edge of any weight and ignores the weights.
+
+```rust
+use graph_clustering_rs::clustering::Clustering;
+use graph_clustering_rs::metrics::GraphMetrics;
+use petgraph::graph::UnGraph;
+
+// two triangles joined by the edge 2-3
+let graph = UnGraph::<(), f64>::from_edges(&[(0, 1, 1.), (0, 2, 1.), (1, 2, 1.), (2, 3, 1.), (3, 4, 1.), (3, 5, 1.), (4, 5, 1.)]);
+let metrics = GraphMetrics::from_graph(&graph).unwrap();
+
+let triangles = Clustering::from_membership(&[0, 0, 0, 1, 1, 1]);
+let one = Clustering::from_membership(&[0; 6]);
+assert!(metrics.modularity(&triangles, 1.).unwrap() > metrics.modularity(&one, 1.).unwrap());
+assert_eq!(metrics.coverage(&triangles).unwrap(), 6. / 7.);
+assert_eq!(metrics.conductance(&triangles).unwrap(), vec![1. / 7., 1. / 7.]);
+assert_eq!(metrics.intra_cluster_density(&triangles).unwrap(), vec![1., 1.]);
+```
+*/
+
+use std::collections::HashSet;
+
+use anyhow::{ensure, Result};
+use ndarray::{ArrayBase, Data, Ix2};
+use num_traits::Float;
+use petgraph::{EdgeType, graph::{Graph, IndexType}};
+
+use crate::clustering::Clustering;
+use crate::network::Network;
+
+/// Weighted undirected graph on which clusterings are scored
+#[derive(Debug, Clone)]
+pub struct GraphMetrics {
+    network: Network,
+}
+
+impl GraphMetrics {
+    /// Metrics on a petgraph graph, edge weights must be finite and not negative
+    pub fn from_graph<N, E: Float, Ty: EdgeType, Ix: IndexType>(graph: &Graph<N, E, Ty, Ix>) -> Result<Self> {
+        Ok(Self { network: Network::from_graph(graph)? })
+    }
+
+    /// Metrics on a square adjacency matrix, such as the input of [`MclExt::mcl`](crate::mcl::MclExt::mcl)
+    ///
+    /// The weight between `i` and `j` is the mean of the entries `(i, j)` and `(j, i)`, and diagonal entries are self loops.
+    pub fn from_adjacency<A, S>(matrix: &ArrayBase<S, Ix2>) -> Result<Self>
+    where
+        A: Float,
+        S: Data<Elem = A>
+    {
+        let (n, m) = matrix.dim();
+        ensure!(n == m, "the adjacency matrix must be square, found {}x{}", n, m);
+
+        let mut edges = Vec::new();
+        for i in 0..n {
+            for j in i..n {
+                let w = (matrix[[i, j]] + matrix[[j, i]]).to_f64().unwrap_or(f64::NAN) / 2.;
+                if w != 0. {
+                    edges.push((i, j, w));
+                }
+            }
+        }
+
+        Ok(Self { network: Network::from_edges(n, edges)? })
+    }
+
+    /// Number of nodes of the graph
+    pub fn node_count(&self) -> usize {
+        self.network.node_count()
+    }
+
+    /// Sum of all edge weights
+    pub fn total_weight(&self) -> f64 {
+        self.network.total_weight
+    }
+
+    /// Modularity of a partition with the resolution `gamma`, unassigned nodes are clusters of their own
+    pub fn modularity(&self, clustering: &Clustering, gamma: f64) -> Result<f64> {
+        self._check(clustering)?;
+        ensure!(!clustering.is_overlapping(), "modularity is defined for partitions, but the clusters overlap");
+
+        let mut membership = Vec::with_capacity(self.node_count());
+        let mut next = clustering.len();
+        for node in 0..self.node_count() {
+            match clustering.clusters_of(node).first() {
+                Some(&c) => membership.push(c),
+                None => {
+                    membership.push(next);
+                    next += 1;
+                }
+            }
+        }
+
+        Ok(self.network.modularity(&membership, gamma))
+    }
+
+    /// Fraction of the edge weight inside the clusters, self loops included, 0 for a graph without edges
+    pub fn coverage(&self, clustering: &Clustering) -> Result<f64> {
+        self._check(clustering)?;
+        if self.network.total_weight == 0. {
+            return Ok(0.);
+        }
+
+        // a self loop is inside the cluster of its node, unassigned nodes being clusters of their own
+        let mut inner: f64 = self.network.self_loops.iter().sum();
+        for node in 0..self.node_count() {
+            for &(j, w) in self.network.adj[node].iter() {
+                if node < j && clustering.share_cluster(node, j) {
+                    inner += w;
+                }
+            }
+        }
+
+        Ok(inner / self.network.total_weight)
+    }
+
+    /// Fraction of the pairs of distinct nodes that are linked inside a cluster or unlinked across clusters, 1 below two nodes
+    pub fn performance(&self, clustering: &Clustering) -> Result<f64> {
+        self._check(clustering)?;
+        let n = self.node_count();
+        if n < 2 {
+            return Ok(1.);
+        }
+
+        let mut intra_edges = 0;
+        let mut inter_edges = 0;
+        for node in 0..n {
+            // parallel edges link a pair once
+            let mut neighbors: Vec<usize> = self.network.adj[node].iter().map(|&(j, _)| j).filter(|&j| j > node).collect();
+            neighbors.sort_unstable();
+            neighbors.dedup();
+
+            let linked_inside = neighbors.iter().filter(|&&j| clustering.share_cluster(node, j)).count();
+            intra_edges += linked_inside;
+            inter_edges += neighbors.len() - linked_inside;
+        }
+
+        let pairs = n * (n - 1) / 2;
+        let inter_non_edges = pairs - self._intra_pairs(clustering) - inter_edges;
+        Ok((intra_edges + inter_non_edges) as f64 / pairs as f64)
+    }
+
+    /// Conductance of every cluster, 0 if the cluster or the rest of the graph has no edges
+    pub fn conductance(&self, clustering: &Clustering) -> Result<Vec<f64>> {
+        self._check(clustering)?;
+        let total_volume = 2. * self.network.total_weight;
+
+        Ok(clustering.iter()
+            .map(|cluster| {
+                let volume: f64 = cluster.iter().map(|&i| self.network.strength[i]).sum();
+                let denominator = volume.min(total_volume - volume);
+                if denominator > 0. { self._cut(cluster) / denominator } else { 0. }
+            })
+            .collect())
+    }
+
+    /// Weight leaving every cluster over the number of pairs of a member and a non-member, 0 for a cluster of every node
+    pub fn cut_ratio(&self, clustering: &Clustering) -> Result<Vec<f64>> {
+        self._check(clustering)?;
+        let n = self.node_count();
+
+        Ok(clustering.iter()
+            .map(|cluster| {
+                let pairs = cluster.len() * (n - cluster.len());
+                if pairs > 0 { self._cut(cluster) / pairs as f64 } else { 0. }
+            })
+            .collect())
+    }
+
+    /// Weight inside every cluster over the number of pairs of its members, self loops excluded, 0 below two members
+    pub fn intra_cluster_density(&self, clustering: &Clustering) -> Result<Vec<f64>> {
+        self._check(clustering)?;
+
+        Ok(clustering.iter()
+            .map(|cluster| {
+                let members: HashSet<usize> = cluster.iter().copied().collect();
+                let inner: f64 = cluster.iter()
+                    .flat_map(|&i| self.network.adj[i].iter().filter(move |&&(j, _)| i < j))
+                    .filter(|(j, _)| members.contains(j))
+                    .map(|&(_, w)| w)
+                    .sum();
+                let pairs = cluster.len() * cluster.len().saturating_sub(1) / 2;
+                if pairs > 0 { inner / pairs as f64 } else { 0. }
+            })
+            .collect())
+    }
+
+    /// Weight between nodes that share no cluster over the number of such pairs, 0 if there are none
+    pub fn inter_cluster_density(&self, clustering: &Clustering) -> Result<f64> {
+        self._check(clustering)?;
+        let n = self.node_count();
+
+        let pairs = n * n.saturating_sub(1) / 2 - self._intra_pairs(clustering);
+        let weight: f64 = (0..n)
+            .flat_map(|node| self.network.adj[node].iter().filter(move |&&(j, _)| j > node && !clustering.share_cluster(node, j)))
+            .map(|&(_, w)| w)
+            .sum();
+
+        Ok(if pairs > 0 { weight / pairs as f64 } else { 0. })
+    }
+
+    fn _check(&self, clustering: &Clustering) -> Result<()> {
+        ensure!(
+            clustering.node_count() <= self.node_count(),
+            "the clustering has {} nodes, but the graph has {}", clustering.node_count(), self.node_count()
+        );
+        Ok(())
+    }
+
+    /// Number of pairs of distinct nodes that share a cluster
+    fn _intra_pairs(&self, clustering: &Clustering) -> usize {
+        if !clustering.is_overlapping() {
+            return clustering.iter().map(|c| c.len() * c.len().saturating_sub(1) / 2).sum();
+        }
+
+        // pairs in several clusters are counted once
+        (0..clustering.node_count())
+            .map(|node| {
+                clustering.clusters_of(node).iter()
+                    .flat_map(|&c| clustering[c].iter().copied())
+                    .filter(|&j| j > node)
+                    .collect::<HashSet<usize>>()
+                    .len()
+            })
+            .sum()
+    }
+
+    /// Weight of the edges between `cluster` and the other nodes
+    fn _cut(&self, cluster: &[usize]) -> f64 {
+        let members: HashSet<usize> = cluster.iter().copied().collect();
+        cluster.iter()
+            .flat_map(|&i| self.network.adj[i].iter())
+            .filter(|(j, _)| !members.contains(j))
+            .map(|&(_, w)| w)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use ndarray::Array2;
+    use petgraph::graph::UnGraph;
+
+    const EDGES: [(u32, u32, f64); 7] = [(0, 1, 1.), (0, 2, 1.), (1, 2, 1.), (2, 3, 1.), (3, 4, 1.), (3, 5, 1.), (4, 5, 1.)];
+
+    fn two_triangles() -> GraphMetrics {
+        GraphMetrics::from_graph(&UnGraph::<(), f64>::from_edges(EDGES.iter())).unwrap()
+    }
+
+    #[test]
+    fn test_partition() {
+        let metrics = two_triangles();
+        let triangles = Clustering::from_membership(&[0, 0, 0, 1, 1, 1]);
+
+        assert_abs_diff_eq!(metrics.modularity(&triangles, 1.).unwrap(), 6. / 7. - 0.5, epsilon = 1e-12);
+        assert_abs_diff_eq!(metrics.modularity(&triangles, 0.).unwrap(), 6. / 7., epsilon = 1e-12);
+        assert_abs_diff_eq!(metrics.coverage(&triangles).unwrap(), 6. / 7., epsilon = 1e-12);
+        assert_abs_diff_eq!(metrics.performance(&triangles).unwrap(), 14. / 15., epsilon = 1e-12);
+        assert_eq!(metrics.cut_ratio(&triangles).unwrap(), vec![1. / 9., 1. / 9.]);
+        assert_abs_diff_eq!(metrics.inter_cluster_density(&triangles).unwrap(), 1. / 9., epsilon = 1e-12);
+
+        // everything in one cluster
+        let one = Clustering::from_membership(&[0; 6]);
+        assert_abs_diff_eq!(metrics.modularity(&one, 1.).unwrap(), 0., epsilon = 1e-12);
+        assert_eq!(metrics.coverage(&one).unwrap(), 1.);
+        assert_eq!(metrics.conductance(&one).unwrap(), vec![0.]);
+        assert_eq!(metrics.cut_ratio(&one).unwrap(), vec![0.]);
+        assert_abs_diff_eq!(metrics.intra_cluster_density(&one).unwrap()[0], 7. / 15., epsilon = 1e-12);
+        assert_eq!(metrics.inter_cluster_density(&one).unwrap(), 0.);
+    }
+
+    #[test]
+    fn test_unassigned_and_overlap() {
+        let metrics = two_triangles();
+
+        // node 5 is unassigned, so it counts as a cluster of its own
+        let partial = Clustering::new(6, vec![vec![0, 1, 2], vec![3, 4]]).unwrap();
+        let full = Clustering::from_membership(&[0, 0, 0, 1, 1, 2]);
+        assert_abs_diff_eq!(metrics.modularity(&partial, 1.).unwrap(), metrics.modularity(&full, 1.).unwrap(), epsilon = 1e-12);
+        assert_abs_diff_eq!(metrics.coverage(&partial).unwrap(), 4. / 7., epsilon = 1e-12);
+        assert_abs_diff_eq!(metrics.performance(&partial).unwrap(), metrics.performance(&full).unwrap(), epsilon = 1e-12);
+
+        // node 2 and 3 in both clusters
+        let overlapping = Clustering::new(6, vec![vec![0, 1, 2, 3], vec![2, 3, 4, 5]]).unwrap();
+        assert!(metrics.modularity(&overlapping, 1.).is_err());
+        assert_eq!(metrics.coverage(&overlapping).unwrap(), 1.);
+        assert_abs_diff_eq!(metrics.performance(&overlapping).unwrap(), 11. / 15., epsilon = 1e-12);
+        // the pair 2-3 shares both clusters but is counted once, which leaves 4 pairs without edges across the clusters
+        assert_eq!(metrics.inter_cluster_density(&overlapping).unwrap(), 0.);
+        assert_abs_diff_eq!(metrics.intra_cluster_density(&overlapping).unwrap()[0], 4. / 6., epsilon = 1e-12);
+
+        let larger = Clustering::new(7, vec![vec![6]]).unwrap();
+        assert!(metrics.coverage(&larger).is_err());
+    }
+
+    #[test]
+    fn test_from_adjacency() {
+        let mut matrix = Array2::<f64>::zeros((6, 6));
+        for &(a, b, w) in EDGES.iter() {
+            matrix[[a as usize, b as usize]] = w;
+            matrix[[b as usize, a as usize]] = w;
+        }
+        let triangles = Clustering::from_membership(&[0, 0, 0, 1, 1, 1]);
+
+        let from_matrix = GraphMetrics::from_adjacency(&matrix).unwrap();
+        let from_graph = two_triangles();
+        assert_eq!(from_matrix.total_weight(), 7.);
+        assert_abs_diff_eq!(from_matrix.modularity(&triangles, 1.).unwrap(), from_graph.modularity(&triangles, 1.).unwrap(), epsilon = 1e-12);
+        assert_eq!(from_matrix.conductance(&triangles).unwrap(), from_graph.conductance(&triangles).unwrap());
+
+        // self loops on the diagonal are inside the clusters of their nodes
+        matrix.diag_mut().iter_mut().for_each(|d| *d = 1.);
+        let with_loops = GraphMetrics::from_adjacency(&matrix).unwrap();
+        assert_eq!(with_loops.total_weight(), 13.);
+        assert_abs_diff_eq!(with_loops.coverage(&triangles).unwrap(), 12. / 13., epsilon = 1e-12);
+        // also the self loop of the unassigned node 5
+        let partial = Clustering::new(6, vec![vec![0, 1, 2], vec![3, 4]]).unwrap();
+        assert_abs_diff_eq!(with_loops.coverage(&partial).unwrap(), 10. / 13., epsilon = 1e-12);
+
+        assert!(GraphMetrics::from_adjacency(&Array2::<f64>::zeros((2, 3))).is_err());
+        assert!(GraphMetrics::from_adjacency(&Array2::from_elem((2, 2), -1.)).is_err());
+    }
+}
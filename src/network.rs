@@ -0,0 +1,214 @@
+/*!
+Weighted undirected network shared by the modularity based algorithms.
+
+Edges are stored in adjacency lists without self loops, which are kept apart so that the
+network of communities built by [`Network::aggregate`] keeps the weight inside each community.
+*/
+
+use std::collections::BTreeMap;
+
+use anyhow::{ensure, Result};
+use num_traits::Float;
+use petgraph::{EdgeType, graph::{Graph, IndexType}, visit::EdgeRef};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Network {
+    /// Neighbors and edge weights of every node, self loops excluded
+    pub adj: Vec<Vec<(usize, f64)>>,
+    /// Weight of the self loop of every node
+    pub self_loops: Vec<f64>,
+    /// Sum of the incident edge weights, self loops counted twice
+    pub strength: Vec<f64>,
+    /// Number of original nodes in every node
+    pub sizes: Vec<f64>,
+    /// Sum of all edge weights
+    pub total_weight: f64,
+}
+
+impl Network {
+    /// Network of a petgraph graph, edge directions are ignored
+    pub fn from_graph<N, E: Float, Ty: EdgeType, Ix: IndexType>(graph: &Graph<N, E, Ty, Ix>) -> Result<Self> {
+        let edges = graph.edge_references().map(|e| (e.source().index(), e.target().index(), e.weight().to_f64().unwrap_or(f64::NAN)));
+        Self::from_edges(graph.node_count(), edges)
+    }
+
+    pub fn from_edges<I: IntoIterator<Item = (usize, usize, f64)>>(n: usize, edges: I) -> Result<Self> {
+        let mut adj = vec![Vec::new(); n];
+        let mut self_loops = vec![0.; n];
+
+        for (a, b, w) in edges {
+            ensure!(w.is_finite() && w >= 0., "edge weights must be finite and not negative, found {}", w);
+            if a == b {
+                self_loops[a] += w;
+            } else {
+                adj[a].push((b, w));
+                adj[b].push((a, w));
+            }
+        }
+
+        Ok(Self::_new(adj, self_loops, vec![1.; n]))
+    }
+
+    fn _new(adj: Vec<Vec<(usize, f64)>>, self_loops: Vec<f64>, sizes: Vec<f64>) -> Self {
+        let strength: Vec<f64> = adj.iter().zip(self_loops.iter())
+            .map(|(a, &l)| a.iter().map(|&(_, w)| w).sum::<f64>() + 2. * l)
+            .collect();
+        let total_weight = strength.iter().sum::<f64>() / 2.;
+
+        Self { adj, self_loops, strength, sizes, total_weight }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.adj.len()
+    }
+
+    /// Network whose nodes are the communities of `membership`, numbered from 0 to `n_communities - 1`
+    pub fn aggregate(&self, membership: &[usize], n_communities: usize) -> Self {
+        let mut adj: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); n_communities];
+        let mut self_loops = vec![0.; n_communities];
+        let mut sizes = vec![0.; n_communities];
+
+        for (i, &c) in membership.iter().enumerate() {
+            self_loops[c] += self.self_loops[i];
+            sizes[c] += self.sizes[i];
+            for &(j, w) in self.adj[i].iter() {
+                let d = membership[j];
+                if c == d {
+                    // the edge is also seen from j
+                    self_loops[c] += w / 2.;
+                } else {
+                    *adj[c].entry(d).or_insert(0.) += w;
+                }
+            }
+        }
+
+        let adj = adj.into_iter().map(|a| a.into_iter().collect()).collect();
+        Self::_new(adj, self_loops, sizes)
+    }
+
+    /// Modularity of `membership` with the resolution `gamma`
+    pub fn modularity(&self, membership: &[usize], gamma: f64) -> f64 {
+        if self.total_weight == 0. {
+            return 0.;
+        }
+
+        let n_communities = membership.iter().max().map_or(0, |&c| c + 1);
+        let mut inner = vec![0.; n_communities];
+        let mut degree = vec![0.; n_communities];
+
+        for (i, &c) in membership.iter().enumerate() {
+            inner[c] += self.self_loops[i];
+            degree[c] += self.strength[i];
+            for &(j, w) in self.adj[i].iter() {
+                if membership[j] == c {
+                    inner[c] += w / 2.;
+                }
+            }
+        }
+
+        let m = self.total_weight;
+        inner.iter().zip(degree.iter())
+            .map(|(&l, &d)| l / m - gamma * (d / (2. * m)).powi(2))
+            .sum()
+    }
+
+    /// Constant Potts Model quality of `membership` with the resolution `gamma`
+    pub fn cpm(&self, membership: &[usize], gamma: f64) -> f64 {
+        let n_communities = membership.iter().max().map_or(0, |&c| c + 1);
+        let mut inner = vec![0.; n_communities];
+        let mut sizes = vec![0.; n_communities];
+
+        for (i, &c) in membership.iter().enumerate() {
+            inner[c] += self.self_loops[i];
+            sizes[c] += self.sizes[i];
+            for &(j, w) in self.adj[i].iter() {
+                if membership[j] == c {
+                    inner[c] += w / 2.;
+                }
+            }
+        }
+
+        inner.iter().zip(sizes.iter())
+            .map(|(&l, &n)| l - gamma * n * (n - 1.) / 2.)
+            .sum()
+    }
+}
+
+/// Members of every community, numbered from 0
+pub(crate) fn clusters_of(membership: &[usize]) -> Vec<Vec<usize>> {
+    let n_communities = membership.iter().max().map_or(0, |&c| c + 1);
+    let mut clusters = vec![Vec::new(); n_communities];
+    for (node, &c) in membership.iter().enumerate() {
+        clusters[c].push(node);
+    }
+    clusters
+}
+
+/// Renumber the communities from 0 in order of first appearance, returning the number of communities
+pub(crate) fn renumber(membership: &mut [usize]) -> usize {
+    let mut ids: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut next = Vec::with_capacity(membership.len());
+
+    for &c in membership.iter() {
+        let n = ids.len();
+        next.push(*ids.entry(c).or_insert(n));
+    }
+    membership.copy_from_slice(&next);
+
+    ids.len()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    /// two triangles joined by the edge 2-3
+    fn two_triangles() -> Network {
+        Network::from_edges(6, vec![
+            (0, 1, 1.), (0, 2, 1.), (1, 2, 1.),
+            (2, 3, 1.),
+            (3, 4, 1.), (3, 5, 1.), (4, 5, 1.),
+        ]).unwrap()
+    }
+
+    #[test]
+    fn test_modularity() {
+        let net = two_triangles();
+        assert_abs_diff_eq!(net.modularity(&[0, 0, 0, 1, 1, 1], 1.), 6. / 7. - 0.5, epsilon = 1e-12);
+        assert_abs_diff_eq!(net.modularity(&[0; 6], 1.), 0., epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_aggregate_keeps_modularity() {
+        let net = two_triangles();
+        let agg = net.aggregate(&[0, 0, 0, 1, 1, 1], 2);
+
+        assert_eq!(agg.self_loops, vec![3., 3.]);
+        assert_eq!(agg.adj, vec![vec![(1, 1.)], vec![(0, 1.)]]);
+        assert_eq!(agg.sizes, vec![3., 3.]);
+        assert_eq!(agg.total_weight, net.total_weight);
+        assert_abs_diff_eq!(agg.modularity(&[0, 1], 1.), net.modularity(&[0, 0, 0, 1, 1, 1], 1.), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_cpm() {
+        let net = two_triangles();
+        assert_abs_diff_eq!(net.cpm(&[0, 0, 0, 1, 1, 1], 0.5), 6. - 0.5 * 6., epsilon = 1e-12);
+        // the quality does not change by aggregation
+        let agg = net.aggregate(&[0, 0, 0, 1, 1, 1], 2);
+        assert_abs_diff_eq!(agg.cpm(&[0, 0], 0.5), net.cpm(&[0; 6], 0.5), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_renumber() {
+        let mut membership = vec![5, 5, 2, 7, 2];
+        assert_eq!(renumber(&mut membership), 3);
+        assert_eq!(membership, vec![0, 0, 1, 2, 1]);
+    }
+
+    #[test]
+    fn test_negative_weight() {
+        assert!(Network::from_edges(2, vec![(0, 1, -1.)]).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+/*!
+Hooks to watch and stop long clustering runs.
+
+An [`Observer`] is called after every iteration with a [`Progress`] and decides with [`Flow`]
+whether the run goes on. Closures work as observers, [`Budget`] stops a run after a number of
+iterations or an amount of wall time, and a tuple of observers stops as soon as one of them does.
+
+```rust
+# #[macro_use] extern crate ndarray;
+use std::time::Duration;
+use graph_clustering_rs::mcl::*;
+use graph_clustering_rs::observer::*;
+use ndarray::Array2;
+
+let input: Array2<f64> = array![[1., 1., 1., 0., 0., 0., 0.],
+                                [1., 1., 1., 0., 0., 0., 0.],
+                                [1., 1., 1., 1., 0., 0., 0.],
+                                [0., 0., 1., 1., 1., 0., 1.],
+                                [0., 0., 0., 1., 1., 1., 1.],
+                                [0., 0., 0., 0., 1., 1., 1.],
+                                [0., 0., 0., 1., 1., 1., 1.]];
+
+let mut chaos = Vec::new();
+let logger = |p: &Progress<MclState<f64>>| {
+    chaos.push(p.state.chaos);
+    Flow::Continue
+};
+let mut observer = (logger, Budget::new().iterations(2).time(Duration::from_secs(60)));
+
+let (_, report) = input.mcl_observed(&MclParams::default(), &mut observer).unwrap();
+assert!(report.interrupted);
+assert!(!report.converged);
+assert_eq!(report.iterations, 2);
+```
+*/
+
+use std::time::Duration;
+
+/// Decision of an [`Observer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// Go on with the next iteration
+    Continue,
+    /// Stop the run and return the current result
+    Stop,
+}
+
+/// Progress of a run passed to an [`Observer`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress<S> {
+    /// Number of finished iterations, starting from 1
+    pub iteration: usize,
+    /// Maximum number of iterations of the run, if known
+    pub total: Option<usize>,
+    /// Wall time since the run started
+    pub elapsed: Duration,
+    /// Algorithm specific state after the iteration
+    pub state: S,
+}
+
+/// Observer of an iterative algorithm
+pub trait Observer<S> {
+    /// Called after every iteration
+    fn observe(&mut self, progress: &Progress<S>) -> Flow;
+}
+
+impl<S, F> Observer<S> for F
+where
+    F: FnMut(&Progress<S>) -> Flow,
+{
+    fn observe(&mut self, progress: &Progress<S>) -> Flow {
+        self(progress)
+    }
+}
+
+impl<S, O1, O2> Observer<S> for (O1, O2)
+where
+    O1: Observer<S>,
+    O2: Observer<S>,
+{
+    fn observe(&mut self, progress: &Progress<S>) -> Flow {
+        // both observers always see the progress
+        match (self.0.observe(progress), self.1.observe(progress)) {
+            (Flow::Continue, Flow::Continue) => Flow::Continue,
+            _ => Flow::Stop,
+        }
+    }
+}
+
+/// Observer that stops the run after a number of iterations or an amount of wall time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Budget {
+    max_iterations: Option<usize>,
+    max_time: Option<Duration>,
+}
+
+impl Budget {
+    /// Budget without any limit
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop after `n` iterations
+    pub fn iterations(mut self, n: usize) -> Self {
+        self.max_iterations = Some(n);
+        self
+    }
+
+    /// Stop once the run took longer than `time`
+    pub fn time(mut self, time: Duration) -> Self {
+        self.max_time = Some(time);
+        self
+    }
+}
+
+impl<S> Observer<S> for Budget {
+    fn observe(&mut self, progress: &Progress<S>) -> Flow {
+        let out_of_iterations = self.max_iterations.is_some_and(|n| progress.iteration >= n);
+        let out_of_time = self.max_time.is_some_and(|t| progress.elapsed >= t);
+
+        if out_of_iterations || out_of_time {
+            Flow::Stop
+        } else {
+            Flow::Continue
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn progress(iteration: usize, elapsed: Duration) -> Progress<()> {
+        Progress { iteration, total: None, elapsed, state: () }
+    }
+
+    #[test]
+    fn test_budget() {
+        let mut budget = Budget::new().iterations(3).time(Duration::from_secs(1));
+        assert_eq!(budget.observe(&progress(1, Duration::from_millis(10))), Flow::Continue);
+        assert_eq!(budget.observe(&progress(3, Duration::from_millis(10))), Flow::Stop);
+        assert_eq!(budget.observe(&progress(1, Duration::from_secs(2))), Flow::Stop);
+        assert_eq!(Budget::new().observe(&progress(1000, Duration::from_secs(1000))), Flow::Continue);
+    }
+
+    #[test]
+    fn test_pair_calls_both() {
+        let mut calls = 0;
+        {
+            let counter = |_: &Progress<()>| {
+                calls += 1;
+                Flow::Continue
+            };
+            let mut pair = (Budget::new().iterations(1), counter);
+            assert_eq!(pair.observe(&progress(1, Duration::default())), Flow::Stop);
+        }
+        assert_eq!(calls, 1);
+    }
+}
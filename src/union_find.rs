@@ -0,0 +1,61 @@
+/*!
+Disjoint sets of the integers `0..n`, shared by the MCL interpretation and the benchmark matching.
+
+The root of a set is always its smallest element, and [`UnionFind::find`] is iterative, so long chains of unions
+cannot overflow the stack.
+*/
+
+#[derive(Debug, Clone)]
+pub(crate) struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    /// `n` singleton sets
+    pub fn new(n: usize) -> Self {
+        Self { parent: (0..n).collect() }
+    }
+
+    /// Smallest element of the set of `x`
+    pub fn find(&mut self, mut x: usize) -> usize {
+        // path halving
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Join the sets of `a` and `b`
+    pub fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parent[a.max(b)] = a.min(b);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(5);
+        sets.union(3, 1);
+        sets.union(4, 3);
+        assert_eq!(sets.find(4), 1);
+        assert_eq!(sets.find(0), 0);
+        assert_eq!(sets.find(2), 2);
+    }
+
+    #[test]
+    fn test_long_chain() {
+        // every union puts the previous root under the new element, which yields a chain as deep as the set
+        let n = 1_000_000;
+        let mut sets = UnionFind::new(n);
+        for x in (0..n - 1).rev() {
+            sets.union(x + 1, x);
+        }
+        assert_eq!(sets.find(n - 1), 0);
+        assert_eq!(sets.find(n / 2), 0);
+    }
+}
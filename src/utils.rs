@@ -74,6 +74,7 @@ impl<R: std::io::Read> Reader<R> {
     pub fn new(r: R) -> Self {
         Reader(r)
     }
+    #[allow(clippy::unbuffered_bytes)]
     pub fn read<T: std::str::FromStr>(&mut self) -> T {
         use std::io::Read;
 